@@ -0,0 +1,196 @@
+//! Command-line display control for scripts and hotkey tools.
+//!
+//! Each invocation creates an [`AsusController`], runs one command and exits
+//! with a nonzero code if it fails:
+//!
+//! ```text
+//! azizo-cli mode eyecare --value 3
+//! azizo-cli ereading on
+//! azizo-cli dimming 60
+//! azizo-cli status
+//! ```
+
+use std::process::ExitCode;
+
+use azizo_core::{AsusController, ControllerError, DisplayController, ModeSnapshot};
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
+use serde::Serialize;
+
+/// Percentage points changed by `dimming-up` and `dimming-down`, as in the GUI.
+const DIMMING_STEP: i32 = 10;
+
+/// Control ASUS Splendid display settings.
+#[derive(Parser)]
+#[command(version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Switch mode; manual takes a --value of 0-100, eyecare 0-4
+    Mode {
+        kind: ModeKindArg,
+        #[arg(long)]
+        value: Option<u8>,
+    },
+    /// Switch to a mode as printed by `status`, e.g. `ereading 4 -10`
+    SetMode {
+        #[arg(required = true, allow_hyphen_values = true)]
+        snapshot: Vec<String>,
+    },
+    /// Turn e-reading on or off
+    Ereading { state: Switch },
+    /// Set dimming (0-100)
+    Dimming {
+        #[arg(value_parser = clap::value_parser!(i32).range(0..=100))]
+        percent: i32,
+    },
+    /// Raise dimming by 10%
+    DimmingUp,
+    /// Lower dimming by 10%
+    DimmingDown,
+    /// Print the current mode and dimming as JSON
+    Status,
+}
+
+/// Modes `mode` can switch to.
+#[derive(Clone, Copy, ValueEnum)]
+enum ModeKindArg {
+    Normal,
+    Vivid,
+    Manual,
+    Eyecare,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Switch {
+    On,
+    Off,
+}
+
+/// Output of `status`.
+#[derive(Serialize)]
+struct Status {
+    /// The mode in the form `set-mode` accepts.
+    mode: String,
+    mode_id: i32,
+    e_reading: bool,
+    /// Dimming percentage, or `None` until the device has reported it.
+    dimming: Option<i32>,
+}
+
+/// A validated command, ready to run against the controller.
+enum Action {
+    Mode(ModeSnapshot),
+    EReading(bool),
+    Dimming(i32),
+    AdjustDimming(i32),
+    Status,
+}
+
+fn main() -> ExitCode {
+    let action = match Action::from_command(Cli::parse().command) {
+        Ok(action) => action,
+        Err(e) => e.exit(),
+    };
+
+    match run(action) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+impl Action {
+    /// Check the arguments clap can't, such as which modes take `--value`.
+    fn from_command(command: Command) -> Result<Self, clap::Error> {
+        let action = match command {
+            Command::Mode { kind, value } => Action::Mode(mode_snapshot(kind, value)?),
+            Command::SetMode { snapshot } => {
+                let snapshot = snapshot
+                    .join(" ")
+                    .parse()
+                    .map_err(|e: ControllerError| invalid(ErrorKind::ValueValidation, e))?;
+                Action::Mode(checked(snapshot)?)
+            }
+            Command::Ereading { state } => Action::EReading(matches!(state, Switch::On)),
+            Command::Dimming { percent } => Action::Dimming(percent),
+            Command::DimmingUp => Action::AdjustDimming(DIMMING_STEP),
+            Command::DimmingDown => Action::AdjustDimming(-DIMMING_STEP),
+            Command::Status => Action::Status,
+        };
+        Ok(action)
+    }
+}
+
+fn mode_snapshot(kind: ModeKindArg, value: Option<u8>) -> Result<ModeSnapshot, clap::Error> {
+    let snapshot = match (kind, value) {
+        (ModeKindArg::Normal, None) => ModeSnapshot::Normal,
+        (ModeKindArg::Vivid, None) => ModeSnapshot::Vivid,
+        (ModeKindArg::Manual, Some(value)) => ModeSnapshot::Manual(value),
+        (ModeKindArg::Eyecare, Some(level)) => ModeSnapshot::EyeCare(level),
+        (ModeKindArg::Manual | ModeKindArg::Eyecare, None) => {
+            return Err(invalid(
+                ErrorKind::MissingRequiredArgument,
+                "manual and eyecare need --value",
+            ));
+        }
+        (ModeKindArg::Normal | ModeKindArg::Vivid, Some(_)) => {
+            return Err(invalid(
+                ErrorKind::ArgumentConflict,
+                "normal and vivid take no --value",
+            ));
+        }
+    };
+    checked(snapshot)
+}
+
+/// Reject out-of-range parameters before the controller is created.
+fn checked(snapshot: ModeSnapshot) -> Result<ModeSnapshot, clap::Error> {
+    snapshot
+        .to_mode()
+        .map(|_| snapshot)
+        .map_err(|e| invalid(ErrorKind::ValueValidation, e))
+}
+
+fn invalid(kind: ErrorKind, message: impl std::fmt::Display) -> clap::Error {
+    Cli::command().error(kind, message)
+}
+
+fn run(action: Action) -> Result<(), ControllerError> {
+    let controller = AsusController::new()?;
+    controller.sync_all_sliders()?;
+
+    match action {
+        Action::Mode(mode) => controller.apply_mode_snapshot(&mode),
+        Action::EReading(on) => {
+            if controller.get_state().is_monochrome != on {
+                controller.toggle_e_reading()?;
+            }
+            Ok(())
+        }
+        Action::Dimming(percent) => controller.set_dimming_percent(percent),
+        Action::AdjustDimming(delta) => {
+            let percent = controller.adjust_dimming_percent(delta)?;
+            println!("{}", percent);
+            Ok(())
+        }
+        Action::Status => {
+            let state = controller.get_state();
+            let status = Status {
+                mode: ModeSnapshot::from_state(&state).to_string(),
+                mode_id: state.mode_id,
+                e_reading: state.is_monochrome,
+                dimming: (state.dimming >= 40).then(|| controller.dimming_percent()),
+            };
+            let json = serde_json::to_string(&status).map_err(std::io::Error::from)?;
+            println!("{}", json);
+            Ok(())
+        }
+    }
+}
@@ -20,7 +20,7 @@ fn main() -> Result<(), ControllerError> {
     println!(
         "Current state: mode={}, dimming={}%",
         state.mode_id,
-        AsusController::dimming_to_percent(state.dimming)
+        controller.dimming_percent()
     );
 
     // Toggle e-reading mode
@@ -0,0 +1,96 @@
+//! Example: Run azizo-core as a Windows service.
+//!
+//! Applies a day/night color temperature ramp every minute while the service
+//! runs, and restores Normal mode when it stops.
+//!
+//! Build with: `cargo build --example windows_service --features service-example`
+//!
+//! Install from an elevated prompt (the path must be absolute):
+//!
+//! ```text
+//! sc create azizo binPath= "C:\path\to\windows_service.exe" start= auto
+//! sc start azizo
+//! ```
+
+use azizo_core::{AsusController, DayRamp, DisplayController, NormalMode};
+use std::ffi::OsString;
+use std::sync::Arc;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::Duration;
+use windows_service::service::{
+    ServiceControl, ServiceControlAccept, ServiceExitCode, ServiceState, ServiceStatus, ServiceType,
+};
+use windows_service::service_control_handler::{self, ServiceControlHandlerResult};
+use windows_service::{define_windows_service, service_dispatcher};
+
+const SERVICE_NAME: &str = "azizo";
+const TICK_INTERVAL: Duration = Duration::from_secs(60);
+
+define_windows_service!(ffi_service_main, service_main);
+
+fn main() -> windows_service::Result<()> {
+    // Blocks until the service is stopped
+    service_dispatcher::start(SERVICE_NAME, ffi_service_main)
+}
+
+fn service_main(_arguments: Vec<OsString>) {
+    if let Err(e) = run_service() {
+        log::error!("service failed: {}", e);
+    }
+}
+
+fn run_service() -> Result<(), Box<dyn std::error::Error>> {
+    let (stop_tx, stop_rx) = mpsc::channel();
+
+    let status_handle =
+        service_control_handler::register(SERVICE_NAME, move |event| match event {
+            ServiceControl::Stop => {
+                let _ = stop_tx.send(());
+                ServiceControlHandlerResult::NoError
+            }
+            // All services must accept Interrogate even if it's a no-op
+            ServiceControl::Interrogate => ServiceControlHandlerResult::NoError,
+            _ => ServiceControlHandlerResult::NotImplemented,
+        })?;
+
+    let set_state = |state: ServiceState, controls: ServiceControlAccept| {
+        status_handle.set_service_status(ServiceStatus {
+            service_type: ServiceType::OWN_PROCESS,
+            current_state: state,
+            controls_accepted: controls,
+            exit_code: ServiceExitCode::Win32(0),
+            checkpoint: 0,
+            wait_hint: Duration::default(),
+            process_id: None,
+        })
+    };
+
+    // Create the controller and the scheduler
+    let controller = Arc::new(AsusController::new()?);
+    controller.sync_all_sliders()?;
+    let ramp = DayRamp::new(controller.clone(), 6500, 3400);
+
+    set_state(ServiceState::Running, ServiceControlAccept::STOP)?;
+
+    // Tick until a stop request arrives
+    loop {
+        if let Err(e) = ramp.tick_now() {
+            log::warn!("ramp tick failed: {}", e);
+        }
+        match stop_rx.recv_timeout(TICK_INTERVAL) {
+            Ok(()) | Err(RecvTimeoutError::Disconnected) => break,
+            Err(RecvTimeoutError::Timeout) => {}
+        }
+    }
+
+    // Leave the display in a neutral state
+    set_state(ServiceState::StopPending, ServiceControlAccept::empty())?;
+    if let Err(e) = controller.set_mode(&NormalMode::new()) {
+        log::warn!("failed to restore Normal mode: {}", e);
+    }
+    drop(ramp);
+    drop(controller);
+
+    set_state(ServiceState::Stopped, ServiceControlAccept::empty())?;
+    Ok(())
+}
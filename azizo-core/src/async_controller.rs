@@ -0,0 +1,213 @@
+//! Async wrapper running controller calls off the caller's thread.
+
+use crate::controller::{AsusController, DisplayController};
+use crate::error::ControllerError;
+use crate::modes::DisplayMode;
+use crate::state::ControllerState;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Async version of the [`DisplayController`] methods.
+///
+/// Queries on [`AsusController`] block while they wait for the DLL's reply
+/// callback, which would stall an async runtime. Each method here runs the
+/// blocking call on tokio's blocking thread pool
+/// ([`spawn_blocking`](tokio::task::spawn_blocking)) and resolves once it
+/// returns, so the methods must be called from within a tokio runtime. A
+/// panic in the call is resumed in the awaiting task.
+///
+/// Methods keep their [`DisplayController`] names. Those that take a mode take
+/// it boxed, since the call outlives the borrow.
+///
+/// Requires the `async` feature.
+///
+/// # Example
+///
+/// ```no_run
+/// use azizo_core::{AsusController, AsyncController, ControllerError};
+///
+/// async fn dim_for_night(controller: &AsyncController) -> Result<(), ControllerError> {
+///     controller.set_dimming_percent(40).await
+/// }
+///
+/// let controller = AsyncController::new(AsusController::new()?);
+/// # let _ = dim_for_night(&controller);
+/// # Ok::<(), ControllerError>(())
+/// ```
+pub struct AsyncController<C = AsusController> {
+    controller: Arc<C>,
+}
+
+impl<C> Clone for AsyncController<C> {
+    fn clone(&self) -> Self {
+        Self {
+            controller: Arc::clone(&self.controller),
+        }
+    }
+}
+
+impl<C: DisplayController + 'static> AsyncController<C> {
+    /// Wrap a controller.
+    pub fn new(controller: C) -> Self {
+        Self::from_arc(Arc::new(controller))
+    }
+
+    /// Wrap a controller that is also used elsewhere.
+    pub fn from_arc(controller: Arc<C>) -> Self {
+        Self { controller }
+    }
+
+    /// The wrapped controller, for calls that don't block.
+    pub fn controller(&self) -> &Arc<C> {
+        &self.controller
+    }
+
+    /// Run `f` with the controller on the blocking thread pool and wait for its result.
+    async fn blocking<T: Send + 'static>(&self, f: impl FnOnce(&C) -> T + Send + 'static) -> T {
+        let controller = Arc::clone(&self.controller);
+        match tokio::task::spawn_blocking(move || f(&controller)).await {
+            Ok(value) => value,
+            // Surface the panic where the call was awaited, as a direct call would
+            Err(e) if e.is_panic() => std::panic::resume_unwind(e.into_panic()),
+            // Blocking tasks are only cancelled when the runtime shuts down
+            Err(e) => panic!("controller call cancelled: {}", e),
+        }
+    }
+
+    /// See [`DisplayController::get_state`].
+    pub async fn get_state(&self) -> ControllerState {
+        self.blocking(|c| c.get_state()).await
+    }
+
+    /// See [`DisplayController::state_blocking`].
+    pub async fn state_blocking(
+        &self,
+        timeout: Duration,
+    ) -> Result<ControllerState, ControllerError> {
+        self.blocking(move |c| c.state_blocking(timeout)).await
+    }
+
+    /// See [`DisplayController::dimming_raw`].
+    pub async fn dimming_raw(&self) -> i32 {
+        self.blocking(|c| c.dimming_raw()).await
+    }
+
+    /// See [`DisplayController::dimming_percent`].
+    pub async fn dimming_percent(&self) -> i32 {
+        self.blocking(|c| c.dimming_percent()).await
+    }
+
+    /// See [`DisplayController::refresh_sliders`].
+    pub async fn refresh_sliders(&self) -> Result<(), ControllerError> {
+        self.blocking(|c| c.refresh_sliders()).await
+    }
+
+    /// See [`DisplayController::sync_all_sliders`].
+    pub async fn sync_all_sliders(&self) -> Result<(), ControllerError> {
+        self.blocking(|c| c.sync_all_sliders()).await
+    }
+
+    /// See [`DisplayController::set_dimming`].
+    pub async fn set_dimming(&self, level: i32) -> Result<(), ControllerError> {
+        self.blocking(move |c| c.set_dimming(level)).await
+    }
+
+    /// See [`DisplayController::set_dimming_splendid_checked`].
+    pub async fn set_dimming_splendid_checked(&self, level: i32) -> Result<(), ControllerError> {
+        self.blocking(move |c| c.set_dimming_splendid_checked(level))
+            .await
+    }
+
+    /// See [`DisplayController::set_dimming_percent`].
+    pub async fn set_dimming_percent(&self, percent: i32) -> Result<(), ControllerError> {
+        self.blocking(move |c| c.set_dimming_percent(percent)).await
+    }
+
+    /// See [`DisplayController::set_base_dimming_percent`].
+    pub async fn set_base_dimming_percent(&self, percent: i32) -> Result<(), ControllerError> {
+        self.blocking(move |c| c.set_base_dimming_percent(percent))
+            .await
+    }
+
+    /// See [`DisplayController::push_dimming_override`].
+    pub async fn push_dimming_override(&self, percent: i32) -> Result<(), ControllerError> {
+        self.blocking(move |c| c.push_dimming_override(percent))
+            .await
+    }
+
+    /// See [`DisplayController::pop_dimming_override`].
+    pub async fn pop_dimming_override(&self) -> Result<(), ControllerError> {
+        self.blocking(|c| c.pop_dimming_override()).await
+    }
+
+    /// See [`DisplayController::base_dimming_percent`].
+    pub async fn base_dimming_percent(&self) -> Option<i32> {
+        self.blocking(|c| c.base_dimming_percent()).await
+    }
+
+    /// See [`DisplayController::adjust_dimming_percent`].
+    pub async fn adjust_dimming_percent(&self, delta: i32) -> Result<i32, ControllerError> {
+        self.blocking(move |c| c.adjust_dimming_percent(delta))
+            .await
+    }
+
+    /// See [`DisplayController::fade_dimming_percent`].
+    pub async fn fade_dimming_percent(
+        &self,
+        target: i32,
+        duration: Duration,
+    ) -> Result<(), ControllerError> {
+        self.blocking(move |c| c.fade_dimming_percent(target, duration))
+            .await
+    }
+
+    /// See [`DisplayController::fade_dimming_percent_steps`].
+    pub async fn fade_dimming_percent_steps(
+        &self,
+        target: i32,
+        duration: Duration,
+        steps: u32,
+    ) -> Result<(), ControllerError> {
+        self.blocking(move |c| c.fade_dimming_percent_steps(target, duration, steps))
+            .await
+    }
+
+    /// See [`DisplayController::get_current_mode`].
+    pub async fn get_current_mode(&self) -> Result<Box<dyn DisplayMode>, ControllerError> {
+        self.blocking(|c| c.get_current_mode()).await
+    }
+
+    /// See [`DisplayController::get_current_mode_fresh`].
+    pub async fn get_current_mode_fresh(&self) -> Result<Box<dyn DisplayMode>, ControllerError> {
+        self.blocking(|c| c.get_current_mode_fresh()).await
+    }
+
+    /// See [`DisplayController::get_current_mode_cached`].
+    pub async fn get_current_mode_cached(&self) -> Result<Box<dyn DisplayMode>, ControllerError> {
+        self.blocking(|c| c.get_current_mode_cached()).await
+    }
+
+    /// See [`DisplayController::set_mode`].
+    pub async fn set_mode(&self, mode: Box<dyn DisplayMode>) -> Result<(), ControllerError> {
+        self.blocking(move |c| c.set_mode(&*mode)).await
+    }
+
+    /// See [`DisplayController::set_mode_if_changed`].
+    pub async fn set_mode_if_changed(
+        &self,
+        mode: Box<dyn DisplayMode>,
+    ) -> Result<bool, ControllerError> {
+        self.blocking(move |c| c.set_mode_if_changed(&*mode)).await
+    }
+
+    /// See [`DisplayController::toggle_e_reading`].
+    pub async fn toggle_e_reading(&self) -> Result<Box<dyn DisplayMode>, ControllerError> {
+        self.blocking(|c| c.toggle_e_reading()).await
+    }
+
+    /// See [`DisplayController::cycle_mode`].
+    pub async fn cycle_mode(&self) -> Result<Box<dyn DisplayMode>, ControllerError> {
+        self.blocking(|c| c.cycle_mode()).await
+    }
+}
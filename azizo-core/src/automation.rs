@@ -0,0 +1,305 @@
+//! Shared controls for automatic (background) display changes.
+
+use log::debug;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+type Deferred = Box<dyn FnOnce() + Send>;
+type Waker = Box<dyn Fn() + Send>;
+
+#[derive(Default)]
+struct GuardState {
+    paused: bool,
+    /// Whether the pause came from [`AutomationGuard::pause_for`] and ends on its own.
+    timed: bool,
+    /// Incremented on every timed pause so superseded timers know to exit.
+    generation: u64,
+    pending: Vec<(&'static str, Deferred)>,
+}
+
+/// A master switch that pauses all automatic display changes.
+///
+/// Background components (such as [`SystemThemeWatcher`](crate::SystemThemeWatcher))
+/// route their changes through [`AutomationGuard::run`]. While the guard is paused,
+/// the latest change from each component is held back and applied on
+/// [`resume`](AutomationGuard::resume), so nothing touches the display during e.g.
+/// a presentation but the display catches up afterwards.
+///
+/// Cloning the guard shares the same switch.
+///
+/// # Example
+///
+/// ```
+/// use azizo_core::AutomationGuard;
+///
+/// let guard = AutomationGuard::new();
+/// // Bind this to a hotkey
+/// let paused = guard.toggle();
+/// assert!(paused);
+/// ```
+#[derive(Clone, Default)]
+pub struct AutomationGuard {
+    state: Arc<Mutex<GuardState>>,
+}
+
+impl AutomationGuard {
+    /// Create a new, unpaused guard.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pause automatic changes.
+    pub fn pause(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.paused = true;
+        state.timed = false;
+        debug!("automation paused");
+    }
+
+    /// Pause automatic changes for `duration`, then resume.
+    ///
+    /// Calling this again while the timed pause lasts restarts the window. A
+    /// pause from [`pause`](Self::pause) takes precedence: it is left as is and
+    /// does not end on its own.
+    pub fn pause_for(&self, duration: Duration) {
+        let generation = {
+            let mut state = self.state.lock().unwrap();
+            if state.paused && !state.timed {
+                return;
+            }
+            state.paused = true;
+            state.timed = true;
+            state.generation += 1;
+            state.generation
+        };
+        debug!("automation paused for {:?}", duration);
+
+        let guard = self.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(duration);
+            let expired = {
+                let state = guard.state.lock().unwrap();
+                state.paused && state.timed && state.generation == generation
+            };
+            if expired {
+                guard.resume();
+            }
+        });
+    }
+
+    /// Resume automatic changes, applying the latest held-back change of each component.
+    pub fn resume(&self) {
+        let pending = {
+            let mut state = self.state.lock().unwrap();
+            state.paused = false;
+            state.timed = false;
+            std::mem::take(&mut state.pending)
+        };
+        debug!(
+            "automation resumed, applying {} pending change(s)",
+            pending.len()
+        );
+        for (source, action) in pending {
+            debug!("applying deferred change from {}", source);
+            action();
+        }
+    }
+
+    /// Toggle between paused and running, returning whether automation is now paused.
+    pub fn toggle(&self) -> bool {
+        if self.is_paused() {
+            self.resume();
+            false
+        } else {
+            self.pause();
+            true
+        }
+    }
+
+    /// Whether automation is currently paused.
+    pub fn is_paused(&self) -> bool {
+        self.state.lock().unwrap().paused
+    }
+
+    /// Run an automatic change, or hold it back until resume if paused.
+    ///
+    /// `source` identifies the component making the change. Only the most recent
+    /// held-back change per source is kept, since earlier targets are no longer relevant.
+    pub fn run(&self, source: &'static str, action: impl FnOnce() + Send + 'static) {
+        {
+            let mut state = self.state.lock().unwrap();
+            if state.paused {
+                debug!("automation paused, deferring change from {}", source);
+                state.pending.retain(|(s, _)| *s != source);
+                state.pending.push((source, Box::new(action)));
+                return;
+            }
+        }
+        action();
+    }
+}
+
+/// Kind of a background automation task.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TaskKind {
+    /// Restore timer of [`AsusController::apply_temporary`](crate::AsusController::apply_temporary).
+    TemporaryMode,
+    /// Enforcement thread of [`AsusController::lock_state`](crate::AsusController::lock_state).
+    StateLock,
+    /// A [`SystemThemeWatcher`](crate::SystemThemeWatcher).
+    ThemeWatcher,
+    /// A [`PowerWatcher`](crate::PowerWatcher).
+    PowerWatcher,
+    /// The polling thread of a [`ScreenTimeTracker`](crate::ScreenTimeTracker).
+    ScreenTimeTracker,
+}
+
+/// A running background task, as listed by [`TaskRegistry::active_tasks`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TaskInfo {
+    /// Identifier to pass to [`TaskRegistry::stop`].
+    pub id: u64,
+    /// What the task does.
+    pub kind: TaskKind,
+    /// When the task was started.
+    pub started_at: SystemTime,
+}
+
+struct RegisteredTask {
+    info: TaskInfo,
+    stop: Arc<AtomicBool>,
+    /// Interrupts the task's wait so it sees `stop` right away.
+    wake: Option<Waker>,
+}
+
+#[derive(Default)]
+struct RegistryState {
+    next_id: u64,
+    tasks: BTreeMap<u64, RegisteredTask>,
+}
+
+/// Tracks the background threads spawned for automation.
+///
+/// Lets an app show e.g. "2 automations running" and stop them individually.
+/// The controller owns one (see
+/// [`AsusController::task_registry`](crate::AsusController::task_registry));
+/// pass it to watchers through [`WatcherOptions::tasks`] to track them too.
+///
+/// Cloning the registry shares the same task list.
+#[derive(Clone, Default)]
+pub struct TaskRegistry {
+    state: Arc<Mutex<RegistryState>>,
+}
+
+impl TaskRegistry {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// List the tasks that are currently running, oldest first.
+    pub fn active_tasks(&self) -> Vec<TaskInfo> {
+        let state = self.state.lock().unwrap();
+        state.tasks.values().map(|task| task.info).collect()
+    }
+
+    /// Ask a task to stop, returning whether it was running.
+    ///
+    /// The task exits at its next check, which may take up to its poll interval.
+    /// Temporary-mode timers are woken and exit right away.
+    pub fn stop(&self, id: u64) -> bool {
+        let task = self.state.lock().unwrap().tasks.remove(&id);
+        match task {
+            Some(task) => {
+                debug!("stopping {:?} task {}", task.info.kind, id);
+                task.stop.store(true, Ordering::SeqCst);
+                if let Some(wake) = task.wake {
+                    wake();
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Record a task whose thread exits once `stop` is set, returning its ID.
+    pub(crate) fn register(&self, kind: TaskKind, stop: Arc<AtomicBool>) -> u64 {
+        self.insert(kind, stop, None)
+    }
+
+    /// Like [`register`](Self::register), calling `wake` after `stop` is set
+    /// so a task blocked in a long wait can exit without finishing it.
+    pub(crate) fn register_with_waker(
+        &self,
+        kind: TaskKind,
+        stop: Arc<AtomicBool>,
+        wake: impl Fn() + Send + 'static,
+    ) -> u64 {
+        self.insert(kind, stop, Some(Box::new(wake)))
+    }
+
+    fn insert(&self, kind: TaskKind, stop: Arc<AtomicBool>, wake: Option<Waker>) -> u64 {
+        let mut state = self.state.lock().unwrap();
+        state.next_id += 1;
+        let id = state.next_id;
+        let info = TaskInfo {
+            id,
+            kind,
+            started_at: SystemTime::now(),
+        };
+        state.tasks.insert(id, RegisteredTask { info, stop, wake });
+        id
+    }
+
+    /// Remove a task once its thread has finished.
+    pub(crate) fn unregister(&self, id: u64) {
+        self.state.lock().unwrap().tasks.remove(&id);
+    }
+}
+
+/// Options shared by the background watchers.
+#[derive(Clone, Default)]
+pub struct WatcherOptions {
+    pub(crate) guard: AutomationGuard,
+    pub(crate) poll_interval: Option<Duration>,
+    pub(crate) tasks: Option<TaskRegistry>,
+}
+
+impl WatcherOptions {
+    /// Create options with default settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Route the watcher's changes through a shared [`AutomationGuard`].
+    pub fn guard(mut self, guard: AutomationGuard) -> Self {
+        self.guard = guard;
+        self
+    }
+
+    /// Set how often the watcher polls for changes.
+    ///
+    /// Each watcher has its own default (2s for
+    /// [`SystemThemeWatcher`](crate::SystemThemeWatcher) and
+    /// [`PowerWatcher`](crate::PowerWatcher), 5s for
+    /// [`ScreenTimeTracker`](crate::ScreenTimeTracker)). Polling less often
+    /// saves battery but reacts to changes later; polling more often makes
+    /// switching snappier at the cost of more wakeups.
+    pub fn poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = Some(interval);
+        self
+    }
+
+    /// List the watcher in a [`TaskRegistry`] while it runs.
+    pub fn tasks(mut self, tasks: TaskRegistry) -> Self {
+        self.tasks = Some(tasks);
+        self
+    }
+
+    /// The configured poll interval, or `default` if none was set.
+    pub(crate) fn poll_interval_or(&self, default: Duration) -> Duration {
+        self.poll_interval.unwrap_or(default)
+    }
+}
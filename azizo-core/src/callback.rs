@@ -0,0 +1,68 @@
+//! Typed payloads of the DLL callback strings.
+
+/// Parsed string payload of the mode callback (func 18).
+///
+/// The DLL reports the mode as a string such as `"0_1_0_1_1,70,0"`: an
+/// underscore-delimited first field, then the dimming level, then the
+/// monochrome flag. The meaning of the underscore fields is not known yet, so
+/// they are exposed as `unknown_N` until they are reverse-engineered.
+///
+/// Fields that are missing or fail to parse are `None`.
+///
+/// # Example
+///
+/// ```
+/// use azizo_core::ModeCallbackPayload;
+///
+/// let payload = ModeCallbackPayload::parse("0_1_0_1_1,70,0");
+/// assert_eq!(payload.unknown_1, Some(1));
+/// assert_eq!(payload.dimming, Some(70));
+/// assert_eq!(payload.monochrome, Some(false));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ModeCallbackPayload {
+    /// First underscore field (meaning unknown).
+    pub unknown_0: Option<i32>,
+    /// Second underscore field (meaning unknown).
+    pub unknown_1: Option<i32>,
+    /// Third underscore field (meaning unknown).
+    pub unknown_2: Option<i32>,
+    /// Fourth underscore field (meaning unknown).
+    pub unknown_3: Option<i32>,
+    /// Fifth underscore field (meaning unknown).
+    pub unknown_4: Option<i32>,
+    /// Dimming level in splendid units (40-100).
+    pub dimming: Option<i32>,
+    /// Whether monochrome/e-reading mode is active.
+    pub monochrome: Option<bool>,
+}
+
+impl ModeCallbackPayload {
+    /// Parse a mode callback string.
+    pub fn parse(s: &str) -> Self {
+        let mut parts = s.split(',');
+
+        let mut unknown = [None; 5];
+        if let Some(first) = parts.next() {
+            for (slot, field) in unknown.iter_mut().zip(first.split('_')) {
+                *slot = field.trim().parse().ok();
+            }
+        }
+        let dimming = parts.next().and_then(|p| p.trim().parse().ok());
+        let monochrome = parts
+            .next()
+            .and_then(|p| p.trim().parse::<i32>().ok())
+            .map(|mono| mono != 0);
+
+        let [unknown_0, unknown_1, unknown_2, unknown_3, unknown_4] = unknown;
+        Self {
+            unknown_0,
+            unknown_1,
+            unknown_2,
+            unknown_3,
+            unknown_4,
+            dimming,
+            monochrome,
+        }
+    }
+}
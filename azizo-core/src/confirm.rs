@@ -0,0 +1,82 @@
+//! Apply a mode that reverts unless the user confirms it.
+
+use crate::controller::{AsusController, DisplayController};
+use crate::state::ControllerState;
+
+use log::info;
+use std::sync::Arc;
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// Handle to a mode change awaiting confirmation.
+///
+/// Obtain with [`AsusController::apply_with_confirmation`]. The previous
+/// settings are restored when the timeout expires, when [`revert`](Self::revert)
+/// is called, or when the handle is dropped, unless [`confirm`](Self::confirm)
+/// was called first.
+pub struct ConfirmationHandle {
+    decision: Option<Sender<bool>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl ConfirmationHandle {
+    pub(crate) fn spawn(
+        controller: Arc<AsusController>,
+        previous: ControllerState,
+        revert_after: Duration,
+    ) -> Self {
+        let (decision, decided) = mpsc::channel();
+
+        let handle = std::thread::spawn(move || {
+            let confirmed = match decided.recv_timeout(revert_after) {
+                Ok(confirmed) => confirmed,
+                Err(RecvTimeoutError::Timeout) => {
+                    info!("change not confirmed within {:?}", revert_after);
+                    false
+                }
+                Err(RecvTimeoutError::Disconnected) => false,
+            };
+            if confirmed {
+                return;
+            }
+
+            info!("reverting unconfirmed change");
+            if let Err(e) = controller.restore_state(&previous) {
+                controller.report_error(&e);
+            }
+        });
+
+        Self {
+            decision: Some(decision),
+            handle: Some(handle),
+        }
+    }
+
+    /// Keep the new settings and cancel the revert.
+    ///
+    /// Has no effect if the timeout already expired.
+    pub fn confirm(mut self) {
+        self.finish(true);
+    }
+
+    /// Restore the previous settings now and wait for them to be applied.
+    pub fn revert(mut self) {
+        self.finish(false);
+    }
+
+    fn finish(&mut self, confirmed: bool) {
+        if let Some(decision) = self.decision.take() {
+            let _ = decision.send(confirmed);
+        }
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for ConfirmationHandle {
+    fn drop(&mut self) {
+        self.finish(false);
+    }
+}
@@ -1,14 +1,31 @@
 //! ASUS display controller implementation.
 
+use crate::automation::{AutomationGuard, TaskInfo, TaskKind, TaskRegistry};
+use crate::confirm::ConfirmationHandle;
+use crate::ddc;
 use crate::error::ControllerError;
-use crate::modes::{DisplayMode, EReadingMode, EyeCareMode, ManualMode, NormalMode, VividMode};
-use crate::state::ControllerState;
+use crate::lock::LockHandle;
+use crate::modes::{
+    self, DisplayMode, EReadingMode, EReadingPreset, EyeCareMode, ManualMode, ModeKind,
+    ModeSnapshot, NormalMode, VividMode,
+};
+use crate::session_log;
+use crate::state::{ControllerState, DimmingLayers, FullStatus, StateDiff, Timestamped};
+use crate::system;
+use crate::worker::CommandSink;
 
 use libloading::{Library, Symbol};
-use log::{debug, info};
+use log::{debug, info, warn};
+use std::collections::hash_map::RandomState;
 use std::ffi::c_void;
 use std::fs;
+use std::hash::BuildHasher;
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
 use windows_sys::Win32::{
     Foundation::ERROR_INSUFFICIENT_BUFFER,
     Storage::Packaging::Appx::{
@@ -18,6 +35,15 @@ use windows_sys::Win32::{
 
 const LOCAL_DLL_NAME: &str = "AsusCustomizationRpcClient.dll";
 
+/// Package family names known to ship the Splendid DLL, tried in order.
+///
+/// MyASUS and Armoury Crate are published under different families
+/// depending on the build.
+pub const PACKAGE_FAMILY_CANDIDATES: &[&str] = &[
+    "B9ECED6F.ASUSPCAssistant_qmba6cd70vzyy",
+    "B9ECED6F.ArmouryCrate_qmba6cd70vzyy",
+];
+
 // =============================================================================
 // Display Controller Trait
 // =============================================================================
@@ -29,6 +55,30 @@ pub trait DisplayController: Send + Sync {
     /// Get a snapshot of the current controller state.
     fn get_state(&self) -> ControllerState;
 
+    /// Get the current state, making sure it reflects the device.
+    ///
+    /// [`get_state`](Self::get_state) may still hold sentinel values if no
+    /// callback has fired yet. On [`AsusController`] this queries the mode and
+    /// all sliders and blocks until their callbacks arrive, failing with
+    /// [`ControllerError::CallbackTimeout`] after `timeout`. The default
+    /// implementation returns [`get_state`](Self::get_state) right away.
+    fn state_blocking(&self, timeout: Duration) -> Result<ControllerState, ControllerError> {
+        let _ = timeout;
+        Ok(self.get_state())
+    }
+
+    /// The current dimming in splendid units (40-100), or `-1` if unknown.
+    fn dimming_raw(&self) -> i32 {
+        self.get_state().dimming
+    }
+
+    /// The current dimming as a percentage (0-100).
+    ///
+    /// See [`AsusController::dimming_to_percent`]; an unknown dimming reads as 0.
+    fn dimming_percent(&self) -> i32 {
+        AsusController::dimming_to_percent(self.dimming_raw())
+    }
+
     /// Refresh slider values from the device.
     fn refresh_sliders(&self) -> Result<(), ControllerError>;
 
@@ -38,57 +88,438 @@ pub trait DisplayController: Send + Sync {
     /// Set the display dimming level (40-100 in splendid units).
     fn set_dimming(&self, level: i32) -> Result<(), ControllerError>;
 
+    /// Set the dimming level in splendid units, rejecting out-of-range values.
+    ///
+    /// Unlike [`set_dimming`](Self::set_dimming), which clamps into 40-100, this
+    /// returns [`ControllerError::InvalidDimming`] for values outside that range.
+    fn set_dimming_splendid_checked(&self, level: i32) -> Result<(), ControllerError> {
+        if !(40..=100).contains(&level) {
+            return Err(ControllerError::InvalidDimming {
+                value: level,
+                min: 40,
+                max: 100,
+            });
+        }
+        self.set_dimming(level)
+    }
+
     /// Set dimming using percentage (0-100).
     fn set_dimming_percent(&self, percent: i32) -> Result<(), ControllerError>;
 
+    /// Set the user's intended dimming percentage (0-100).
+    ///
+    /// Applied right away unless a dimming override is active, in which case it
+    /// takes effect once all overrides are popped. Use this for user-driven
+    /// changes so automation can't permanently clobber them.
+    fn set_base_dimming_percent(&self, percent: i32) -> Result<(), ControllerError>;
+
+    /// Apply a transient dimming percentage (0-100) on top of the base dimming.
+    ///
+    /// For automation such as dimming on battery. If no base was set yet, the
+    /// current dimming becomes the base. Overrides stack; see
+    /// [`pop_dimming_override`](Self::pop_dimming_override).
+    fn push_dimming_override(&self, percent: i32) -> Result<(), ControllerError>;
+
+    /// Remove the newest dimming override and re-apply the next one, or the base.
+    ///
+    /// Does nothing if no override is active.
+    fn pop_dimming_override(&self) -> Result<(), ControllerError>;
+
+    /// The user's intended dimming percentage, if one is known.
+    ///
+    /// [`get_state`](Self::get_state) reports the effective dimming, which
+    /// differs from this while an override is active.
+    fn base_dimming_percent(&self) -> Option<i32>;
+
+    /// Change the user's dimming by `delta` percentage points, clamped to 0-100.
+    ///
+    /// For hotkeys and CLI steps. Starts from the
+    /// [`base_dimming_percent`](Self::base_dimming_percent), or the current
+    /// dimming if none is set, and writes back through
+    /// [`set_base_dimming_percent`](Self::set_base_dimming_percent). Returns the
+    /// new percentage.
+    fn adjust_dimming_percent(&self, delta: i32) -> Result<i32, ControllerError> {
+        let current = self
+            .base_dimming_percent()
+            .unwrap_or_else(|| self.dimming_percent());
+        let percent = (current + delta).clamp(0, 100);
+        self.set_base_dimming_percent(percent)?;
+        Ok(percent)
+    }
+
+    /// Fade dimming to `target` percent (0-100) over `duration`.
+    ///
+    /// Uses one step per 16ms frame; see
+    /// [`fade_dimming_percent_steps`](Self::fade_dimming_percent_steps).
+    fn fade_dimming_percent(&self, target: i32, duration: Duration) -> Result<(), ControllerError> {
+        let steps = (duration.as_millis() / 16).max(1) as u32;
+        self.fade_dimming_percent_steps(target, duration, steps)
+    }
+
+    /// Fade dimming to `target` percent (0-100) in `steps` evenly spaced
+    /// [`set_dimming`](Self::set_dimming) calls across `duration`.
+    ///
+    /// Blocks until the fade is done. Splendid dimming has only 61 levels, so
+    /// the step count is capped at the number of levels in between. Does
+    /// nothing if the dimming is already at the target, and jumps straight
+    /// there if the current dimming is unknown.
+    fn fade_dimming_percent_steps(
+        &self,
+        target: i32,
+        duration: Duration,
+        steps: u32,
+    ) -> Result<(), ControllerError> {
+        let target = AsusController::percent_to_dimming(target.clamp(0, 100));
+        let current = self.get_state().dimming;
+        if current == target {
+            return Ok(());
+        }
+        if current < 0 {
+            return self.set_dimming(target);
+        }
+
+        let steps = steps.clamp(1, current.abs_diff(target));
+        let interval = duration / steps;
+        for step in 1..steps {
+            let level = current + (target - current) * step as i32 / steps as i32;
+            self.set_dimming(level)?;
+            std::thread::sleep(interval);
+        }
+        self.set_dimming(target)
+    }
+
     /// Get the current display mode.
+    ///
+    /// On [`AsusController`] this queries the device and waits for the reply
+    /// callback.
     fn get_current_mode(&self) -> Result<Box<dyn DisplayMode>, ControllerError>;
 
+    /// Get the current display mode, querying the device first.
+    ///
+    /// Same as [`get_current_mode`](Self::get_current_mode); use this where
+    /// freshness matters, e.g. behind an explicit refresh button.
+    fn get_current_mode_fresh(&self) -> Result<Box<dyn DisplayMode>, ControllerError> {
+        self.get_current_mode()
+    }
+
+    /// Get the current display mode from the cached state, without querying the device.
+    ///
+    /// The cache is kept up to date by device callbacks, so this is usually
+    /// current and avoids the query latency of
+    /// [`get_current_mode_fresh`](Self::get_current_mode_fresh).
+    fn get_current_mode_cached(&self) -> Result<Box<dyn DisplayMode>, ControllerError> {
+        modes::mode_from_state(&self.get_state())
+    }
+
     /// Set a display mode.
     fn set_mode(&self, mode: &dyn DisplayMode) -> Result<(), ControllerError>;
 
+    /// Set a display mode only if it differs from the cached current mode.
+    ///
+    /// Returns whether the mode was applied. See [`DisplayMode::needs_apply`].
+    fn set_mode_if_changed(&self, mode: &dyn DisplayMode) -> Result<bool, ControllerError> {
+        if let Ok(current) = modes::mode_from_state(&self.get_state())
+            && !mode.needs_apply(&*current)
+        {
+            return Ok(false);
+        }
+        self.set_mode(mode)?;
+        Ok(true)
+    }
+
+    /// Report an error from a background operation (timer, watcher, worker).
+    ///
+    /// The default implementation logs the error. [`AsusController`] also
+    /// forwards it to the hook set with [`AsusController::set_error_hook`].
+    fn report_error(&self, error: &ControllerError) {
+        warn!("background operation failed: {}", error);
+    }
+
     /// Toggle e-reading mode on/off.
+    ///
+    /// The dimming level is preserved across the toggle.
     fn toggle_e_reading(&self) -> Result<Box<dyn DisplayMode>, ControllerError>;
+
+    /// Switch to the next mode in the order Normal, Vivid, Manual, Eye Care.
+    ///
+    /// For a "next mode" hotkey. The mode after Eye Care, or after an unknown
+    /// mode, is Normal. Manual and Eye Care use the slider values from
+    /// [`get_state`](Self::get_state). If e-reading is on, the underlying mode
+    /// is cycled and e-reading is switched back on over it. Returns the new
+    /// underlying mode.
+    fn cycle_mode(&self) -> Result<Box<dyn DisplayMode>, ControllerError> {
+        let state = self.get_state();
+        let next = match state.mode_id {
+            1 => ModeKind::Vivid,
+            2 => ModeKind::Manual,
+            6 => ModeKind::EyeCare,
+            _ => ModeKind::Normal,
+        }
+        .to_mode(None, &state)?;
+        self.set_mode(&*next)?;
+        if state.is_monochrome {
+            self.set_mode(&EReadingMode::from_controller_state(&state))?;
+        }
+        Ok(next)
+    }
 }
 
 // =============================================================================
 // Callback State (private module with globals)
 // =============================================================================
 
+// The DLL invokes `mode_callback` on its own thread, concurrently with user
+// calls reading `snapshot`. All cached values live behind one lock, and each
+// callback updates its fields under a single acquisition, so a snapshot never
+// mixes fields from consecutive callbacks (e.g. the new mode with the old
+// dimming).
 mod callback_state {
     use super::ControllerState;
-    use log::{debug, trace};
-    use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
+    use crate::callback::ModeCallbackPayload;
+    use crate::modes::ModeSnapshot;
+    use crate::session_log;
+    use crate::state::{StateDiff, StateTimestamps};
+    use log::{Level, log, log_enabled};
+    use std::collections::VecDeque;
+    use std::fmt;
+    use std::sync::mpsc::Sender;
+    use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+    use std::time::{Duration, Instant};
+
+    /// Callback code reporting the mode, dimming and monochrome flag.
+    pub(super) const MODE: i32 = 18;
+    /// Callback code reporting the manual slider.
+    pub(super) const MANUAL: i32 = 20;
+    /// Callback code reporting the eye care level.
+    pub(super) const EYECARE: i32 = 21;
+    /// Callback code reporting the e-reading grayscale and temperature.
+    pub(super) const MONOCHROME: i32 = 27;
+
+    /// The cached values before any callback, restored by [`reset`].
+    const INITIAL: ControllerState = ControllerState {
+        mode_id: -1,
+        is_monochrome: false,
+        dimming: -1,
+        manual_slider: 50,
+        eyecare_level: 2,
+        ereading_grayscale: 4,
+        ereading_temp: 0,
+        last_non_ereading_mode: -1,
+        raw_callback: None,
+    };
 
-    static CURRENT_MODE: AtomicI32 = AtomicI32::new(-1);
-    static IS_MONOCHROME: AtomicBool = AtomicBool::new(false);
-    static LAST_NON_EREADING_MODE: AtomicI32 = AtomicI32::new(1);
+    static STATE: Mutex<ControllerState> = Mutex::new(INITIAL);
 
-    static MANUAL_SLIDER: AtomicI32 = AtomicI32::new(50);
-    static EYECARE_SLIDER: AtomicI32 = AtomicI32::new(2);
-    static EREADING_GRAYSCALE: AtomicI32 = AtomicI32::new(4);
-    static EREADING_TEMP: AtomicI32 = AtomicI32::new(0);
-    static CURRENT_DIMMING: AtomicI32 = AtomicI32::new(-1);
+    static PREVIOUS_MODE: Mutex<Option<ModeSnapshot>> = Mutex::new(None);
 
-    pub(super) fn snapshot() -> ControllerState {
-        ControllerState {
-            mode_id: CURRENT_MODE.load(Ordering::SeqCst),
-            is_monochrome: IS_MONOCHROME.load(Ordering::SeqCst),
-            dimming: CURRENT_DIMMING.load(Ordering::SeqCst),
-            manual_slider: MANUAL_SLIDER.load(Ordering::SeqCst) as u8,
-            eyecare_level: EYECARE_SLIDER.load(Ordering::SeqCst) as u8,
-            ereading_grayscale: EREADING_GRAYSCALE.load(Ordering::SeqCst) as u8,
-            ereading_temp: EREADING_TEMP.load(Ordering::SeqCst) as i8,
-            last_non_ereading_mode: LAST_NON_EREADING_MODE.load(Ordering::SeqCst),
+    static UPDATED: Mutex<StateTimestamps> = Mutex::new(StateTimestamps::new());
+
+    static HISTORY: Mutex<Option<History>> = Mutex::new(None);
+    static SUBSCRIBERS: Mutex<Vec<Sender<ControllerState>>> = Mutex::new(Vec::new());
+    type SharedEReadingHook = Arc<dyn Fn(bool) + Send + Sync>;
+    static E_READING_HOOKS: Mutex<Vec<SharedEReadingHook>> = Mutex::new(Vec::new());
+
+    pub(super) fn on_e_reading_change(hook: super::EReadingHook) {
+        E_READING_HOOKS.lock().unwrap().push(Arc::from(hook));
+    }
+
+    pub(super) fn clear_e_reading_hooks() {
+        E_READING_HOOKS.lock().unwrap().clear();
+    }
+
+    fn notify_e_reading(active: bool) {
+        // Cloned so a hook can register another one without deadlocking
+        let hooks = E_READING_HOOKS.lock().unwrap().clone();
+        for hook in hooks {
+            hook(active);
+        }
+    }
+
+    pub(super) fn subscribe(sender: Sender<ControllerState>) {
+        SUBSCRIBERS.lock().unwrap().push(sender);
+    }
+
+    /// Send the current state to every subscriber, dropping disconnected ones.
+    fn notify_subscribers() {
+        let mut subscribers = SUBSCRIBERS.lock().unwrap();
+        if subscribers.is_empty() {
+            return;
+        }
+        let state = snapshot();
+        subscribers.retain(|tx| tx.send(state.clone()).is_ok());
+    }
+
+    /// Bounded timeline of state changes seen by the callback.
+    struct History {
+        last: ControllerState,
+        entries: VecDeque<(Instant, StateDiff)>,
+        limit: usize,
+    }
+
+    impl History {
+        fn record(&mut self, current: ControllerState) {
+            let Some(diff) = current.diff(&self.last) else {
+                return;
+            };
+            if self.entries.len() == self.limit {
+                self.entries.pop_front();
+            }
+            self.entries.push_back((Instant::now(), diff));
+            self.last = current;
+        }
+    }
+
+    /// Start recording state changes, keeping at most `limit` entries.
+    pub(super) fn enable_history(limit: usize) {
+        *HISTORY.lock().unwrap() = Some(History {
+            last: snapshot(),
+            entries: VecDeque::with_capacity(limit.min(1024)),
+            limit: limit.max(1),
+        });
+    }
+
+    pub(super) fn disable_history() {
+        *HISTORY.lock().unwrap() = None;
+    }
+
+    pub(super) fn history() -> Vec<(Instant, StateDiff)> {
+        HISTORY
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|h| h.entries.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    // One counter per callback code, bumped after its values are stored, so a
+    // caller can wait for the reply to a request instead of sleeping blindly.
+    static RECEIVED: Mutex<[u64; 4]> = Mutex::new([0; 4]);
+    static RECEIVED_CHANGED: Condvar = Condvar::new();
+
+    // Dragging a slider fires the callback dozens of times with the same values,
+    // so identical consecutive log lines are collapsed into one "(xN)" line.
+    static TRACE_LOG: LogCoalescer = LogCoalescer::new(Level::Trace);
+    static DEBUG_LOG: LogCoalescer = LogCoalescer::new(Level::Debug);
+
+    struct RepeatedLine {
+        line: String,
+        count: u32,
+    }
+
+    /// Logs a line unless it repeats the previous one, counting the repeats.
+    ///
+    /// The count is written when a different line arrives, as the repeated line
+    /// followed by the total number of times it occurred, e.g.
+    /// `"manual slider updated: 50 (x12)"`.
+    struct LogCoalescer {
+        level: Level,
+        last: Mutex<Option<RepeatedLine>>,
+    }
+
+    impl LogCoalescer {
+        const fn new(level: Level) -> Self {
+            Self {
+                level,
+                last: Mutex::new(None),
+            }
+        }
+
+        fn log(&self, args: fmt::Arguments) {
+            if !log_enabled!(self.level) {
+                return;
+            }
+            let line = args.to_string();
+            let mut last = self.last.lock().unwrap();
+            if let Some(repeated) = last.as_mut()
+                && repeated.line == line
+            {
+                repeated.count += 1;
+                return;
+            }
+            if let Some(previous) = last.take()
+                && previous.count > 1
+            {
+                log!(self.level, "{} (x{})", previous.line, previous.count);
+            }
+            log!(self.level, "{}", line);
+            *last = Some(RepeatedLine { line, count: 1 });
         }
     }
 
+    fn state() -> MutexGuard<'static, ControllerState> {
+        STATE.lock().unwrap()
+    }
+
+    pub(super) fn snapshot() -> ControllerState {
+        state().clone()
+    }
+
+    pub(super) fn timestamps() -> StateTimestamps {
+        *UPDATED.lock().unwrap()
+    }
+
+    fn touch(field: impl FnOnce(&mut StateTimestamps) -> &mut Option<Instant>) {
+        *field(&mut UPDATED.lock().unwrap()) = Some(Instant::now());
+    }
+
+    /// Reset all cached values to their startup defaults.
+    pub(super) fn reset() {
+        *state() = INITIAL;
+        *PREVIOUS_MODE.lock().unwrap() = None;
+        *UPDATED.lock().unwrap() = StateTimestamps::new();
+    }
+
     pub(super) fn store_last_non_ereading_mode(mode_id: i32) {
-        LAST_NON_EREADING_MODE.store(mode_id, Ordering::SeqCst);
+        state().last_non_ereading_mode = mode_id;
+    }
+
+    fn slot(func: i32) -> Option<usize> {
+        match func {
+            MODE => Some(0),
+            MANUAL => Some(1),
+            EYECARE => Some(2),
+            MONOCHROME => Some(3),
+            _ => None,
+        }
+    }
+
+    /// Number of callbacks received so far for a callback code.
+    pub(super) fn received(func: i32) -> u64 {
+        slot(func).map_or(0, |i| RECEIVED.lock().unwrap()[i])
+    }
+
+    /// Wait until more than `since` callbacks have been received for `func`.
+    ///
+    /// Returns `false` if none arrived within the timeout.
+    pub(super) fn wait_for(func: i32, since: u64, timeout: Duration) -> bool {
+        let Some(i) = slot(func) else {
+            return false;
+        };
+        let received = RECEIVED.lock().unwrap();
+        let (received, _) = RECEIVED_CHANGED
+            .wait_timeout_while(received, timeout, |received| received[i] <= since)
+            .unwrap();
+        received[i] > since
+    }
+
+    fn signal(func: i32) {
+        if let Some(i) = slot(func) {
+            RECEIVED.lock().unwrap()[i] += 1;
+            RECEIVED_CHANGED.notify_all();
+        }
+    }
+
+    pub(super) fn store_previous_mode(mode: ModeSnapshot) {
+        *PREVIOUS_MODE.lock().unwrap() = Some(mode);
+    }
+
+    pub(super) fn previous_mode() -> Option<ModeSnapshot> {
+        *PREVIOUS_MODE.lock().unwrap()
     }
 
     pub(super) fn store_dimming(value: i32) {
-        CURRENT_DIMMING.store(value, Ordering::SeqCst);
+        state().dimming = value;
+        touch(|t| &mut t.dimming);
     }
 
     pub(super) extern "C" fn mode_callback(func: i32, data: i32, str_data: *const i8) {
@@ -102,49 +533,80 @@ mod callback_state {
             }
         };
 
-        trace!("callback: func={}, data={}, str='{}'", func, data, s);
+        TRACE_LOG.log(format_args!(
+            "callback: func={}, data={}, str='{}'",
+            func, data, s
+        ));
+        session_log::record(format_args!(
+            "callback: func={}, data={}, str='{}'",
+            func, data, s
+        ));
 
         match func {
-            18 => {
-                let parts: Vec<&str> = s.split(',').collect();
-                if parts.len() >= 2 {
-                    if let Ok(dimming) = parts[1].parse::<i32>() {
-                        CURRENT_DIMMING.store(dimming, Ordering::SeqCst);
+            MODE => {
+                let payload = ModeCallbackPayload::parse(&s);
+                let (toggled, dimming, monochrome) = {
+                    let mut state = state();
+                    if let Some(dimming) = payload.dimming {
+                        state.dimming = dimming;
                     }
-                }
-                if parts.len() >= 3 {
-                    if let Ok(mono) = parts[2].parse::<i32>() {
-                        IS_MONOCHROME.store(mono != 0, Ordering::SeqCst);
+                    // The first report is the initial state, not a toggle
+                    let first_report = state.mode_id == -1;
+                    state.mode_id = data;
+                    let toggled = payload
+                        .monochrome
+                        .filter(|&mono| mono != state.is_monochrome && !first_report);
+                    if let Some(mono) = payload.monochrome {
+                        state.is_monochrome = mono;
                     }
+                    state.raw_callback = Some(s);
+                    (toggled, state.dimming, state.is_monochrome)
+                };
+                if payload.dimming.is_some() {
+                    touch(|t| &mut t.dimming);
+                }
+                touch(|t| &mut t.mode);
+                // Outside the lock, as hooks may read the state
+                if let Some(mono) = toggled {
+                    notify_e_reading(mono);
                 }
-                CURRENT_MODE.store(data, Ordering::SeqCst);
 
-                debug!(
+                DEBUG_LOG.log(format_args!(
                     "mode updated: data={}, dimming={}, monochrome={}",
-                    data,
-                    CURRENT_DIMMING.load(Ordering::SeqCst),
-                    IS_MONOCHROME.load(Ordering::SeqCst)
-                );
+                    data, dimming, monochrome
+                ));
             }
-            20 => {
-                MANUAL_SLIDER.store(data, Ordering::SeqCst);
-                debug!("manual slider updated: {}", data);
+            MANUAL => {
+                state().manual_slider = data as u8;
+                touch(|t| &mut t.manual_slider);
+                DEBUG_LOG.log(format_args!("manual slider updated: {}", data));
             }
-            21 => {
-                EYECARE_SLIDER.store(data, Ordering::SeqCst);
-                debug!("eyecare slider updated: {}", data);
+            EYECARE => {
+                state().eyecare_level = data as u8;
+                touch(|t| &mut t.eyecare_level);
+                DEBUG_LOG.log(format_args!("eyecare slider updated: {}", data));
             }
-            27 => {
-                let raw = data + 206;
-                let grayscale = raw / 256;
-                let temp = raw % 256;
+            MONOCHROME => {
+                let (grayscale, temp) = super::AsusController::decode_monochrome(data);
                 // Hardware uses 1-5 directly, no conversion needed
-                EREADING_GRAYSCALE.store(grayscale, Ordering::SeqCst);
-                EREADING_TEMP.store(temp, Ordering::SeqCst);
-                debug!("e-reading updated: grayscale={}, temp={}", grayscale, temp);
+                {
+                    let mut state = state();
+                    state.ereading_grayscale = grayscale as u8;
+                    state.ereading_temp = temp as i8;
+                }
+                touch(|t| &mut t.ereading);
+                DEBUG_LOG.log(format_args!(
+                    "e-reading updated: grayscale={}, temp={}",
+                    grayscale, temp
+                ));
             }
             _ => {}
         }
+        if let Some(history) = HISTORY.lock().unwrap().as_mut() {
+            history.record(snapshot());
+        }
+        notify_subscribers();
+        signal(func);
     }
 }
 
@@ -174,141 +636,1613 @@ static INSTANCE_EXISTS: AtomicBool = AtomicBool::new(false);
 ///
 /// # Limitations
 ///
-/// Only one instance can exist at a time due to DLL/RPC constraints.
+/// Only one instance can exist at a time due to DLL/RPC constraints. Use
+/// [`clone_handle`](Self::clone_handle) for another handle to the same instance.
 pub struct AsusController {
+    shared: Arc<SharedDll>,
+    config: ControllerConfig,
+    temporary: Mutex<TemporaryMode>,
+    coalesced: Mutex<CoalescedMode>,
+    dimming_layers: Mutex<DimmingLayers>,
+    tasks: TaskRegistry,
+    automation: AutomationGuard,
+    error_hook: RwLock<Option<ErrorHook>>,
+    value_mapper: RwLock<Option<ValueMapper>>,
+    mirror_external: AtomicBool,
+    /// Sets external monitor brightness for mirroring, returning how many
+    /// monitors took it. [`ddc::set_external_brightness`] outside tests.
+    external_brightness: fn(u32) -> usize,
+    /// Whether [`on_power_change`](AsusController::on_power_change) pushed the battery override.
+    power_override: AtomicBool,
+}
+
+/// The loaded library, shared by every handle of one controller.
+///
+/// Dropping the last handle unloads the DLL and releases the instance guard.
+struct SharedDll {
+    dll: RwLock<Option<Dll>>,
+    /// A [`system::OwnerRecord`], held so other processes can find this one via
+    /// [`AsusController::current_owner_pid`].
+    ///
+    /// Boxed as `dyn` so that only code claiming a record links the Windows
+    /// calls releasing it, which lets the tests build controllers elsewhere.
+    _owner: Option<Box<dyn Send + Sync>>,
+}
+
+impl Drop for SharedDll {
+    fn drop(&mut self) {
+        drop(self.dll.get_mut().unwrap().take());
+        callback_state::clear_e_reading_hooks();
+        INSTANCE_EXISTS.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Another handle to an [`AsusController`], from [`AsusController::clone_handle`].
+pub type AsusControllerHandle = AsusController;
+
+/// Callback invoked when a background operation fails.
+///
+/// See [`AsusController::set_error_hook`].
+pub type ErrorHook = Box<dyn Fn(&ControllerError) + Send + Sync>;
+
+/// Callback invoked with whether e-reading is now on.
+///
+/// See [`AsusController::on_e_reading_change`].
+pub type EReadingHook = Box<dyn Fn(bool) + Send + Sync>;
+
+/// Transform applied to mode values before they are sent to the DLL.
+///
+/// See [`AsusController::set_value_mapper`].
+pub type ValueMapper = Box<dyn Fn(ModeKind, u8) -> u8 + Send + Sync>;
+
+/// Bookkeeping for [`AsusController::apply_temporary`].
+#[derive(Default)]
+struct TemporaryMode {
+    /// Incremented on every call so superseded timers know to exit.
+    generation: u64,
+    /// The state to restore once the active temporary mode expires.
+    restore: Option<ControllerState>,
+    /// Registry ID of the active timer.
+    task: Option<u64>,
+}
+
+/// Bookkeeping for [`AsusController::set_mode_coalesced`].
+#[derive(Default)]
+struct CoalescedMode {
+    /// Incremented on every call so superseded workers know to exit.
+    generation: u64,
+    /// The most recently requested mode, not yet sent to the device.
+    pending: Option<Box<dyn DisplayMode>>,
+}
+
+type GetFn = unsafe extern "C" fn(*mut c_void) -> i64;
+type SetModeFn = unsafe extern "C" fn(u8, *const i8, *mut c_void) -> i64;
+type SetMonoFn = unsafe extern "C" fn(i32, *mut c_void) -> i64;
+type SetDimmingFn = unsafe extern "C" fn(i32, *const i8, *mut c_void) -> i64;
+
+/// Getter exports resolved when the DLL is loaded.
+const KNOWN_GETTERS: &[&[u8]] = &[
+    b"MyOptGetSplendidColorModeFunc",
+    b"MyOptGetSplendidManualModeFunc",
+    b"MyOptGetSplendidEyecareModeFunc",
+    b"MyOptGetSplendidMonochromeFunc",
+];
+
+/// Mode setter exports resolved when the DLL is loaded.
+const KNOWN_SETTERS: &[&[u8]] = &[
+    b"MyOptSetSplendidFunc",
+    b"MyOptSetSplendidManualFunc",
+    b"MyOptSetSplendidEyecareFunc",
+];
+
+/// Function pointers for the known exports, resolved once per load.
+///
+/// A missing export is simply left out; calling it then fails through the
+/// lazy lookup in [`Dll::resolve`] with the usual error.
+struct Symbols {
+    getters: Vec<(&'static [u8], GetFn)>,
+    setters: Vec<(&'static [u8], SetModeFn)>,
+    set_monochrome: Option<SetMonoFn>,
+    set_dimming: Option<SetDimmingFn>,
+}
+
+impl Symbols {
+    /// # Safety
+    ///
+    /// The pointers are only valid while `lib` stays loaded.
+    unsafe fn resolve(lib: &Library) -> Self {
+        unsafe {
+            let get = |symbol: &'static [u8]| lib.get::<GetFn>(symbol).ok().map(|f| (symbol, *f));
+            let set =
+                |symbol: &'static [u8]| lib.get::<SetModeFn>(symbol).ok().map(|f| (symbol, *f));
+            Self {
+                getters: KNOWN_GETTERS.iter().filter_map(|s| get(s)).collect(),
+                setters: KNOWN_SETTERS.iter().filter_map(|s| set(s)).collect(),
+                set_monochrome: lib
+                    .get::<SetMonoFn>(b"MyOptSetSplendidMonochromeFunc")
+                    .ok()
+                    .map(|f| *f),
+                set_dimming: lib
+                    .get::<SetDimmingFn>(b"MyOptSetSplendidDimmingFunc")
+                    .ok()
+                    .map(|f| *f),
+            }
+        }
+    }
+
+    /// Whether a known export was found, or `None` if `symbol` isn't a known one.
+    fn contains(&self, symbol: &[u8]) -> Option<bool> {
+        if KNOWN_GETTERS.contains(&symbol) {
+            return Some(find(&self.getters, symbol).is_some());
+        }
+        if KNOWN_SETTERS.contains(&symbol) {
+            return Some(find(&self.setters, symbol).is_some());
+        }
+        match symbol {
+            b"MyOptSetSplendidMonochromeFunc" => Some(self.set_monochrome.is_some()),
+            b"MyOptSetSplendidDimmingFunc" => Some(self.set_dimming.is_some()),
+            _ => None,
+        }
+    }
+}
+
+/// A private copy of the DLL in the temp directory, removed on drop.
+struct TempCopy {
+    dir: PathBuf,
+}
+
+impl TempCopy {
+    /// Copy the DLL into `%TEMP%\azizo-<pid>`.
+    fn create(dll_path: &str) -> Result<(Self, PathBuf), ControllerError> {
+        let dir = std::env::temp_dir().join(format!("azizo-{}", std::process::id()));
+        fs::create_dir_all(&dir)?;
+        let copy = Self { dir };
+        let target = copy.dir.join(LOCAL_DLL_NAME);
+        fs::copy(dll_path, &target)?;
+
+        // A truncated copy fails to load with a cryptic error, so check it first
+        let expected = fs::metadata(dll_path)?.len();
+        let actual = fs::metadata(&target)?.len();
+        if actual != expected {
+            return Err(ControllerError::IncompleteDllCopy { expected, actual });
+        }
+        Ok((copy, target))
+    }
+}
+
+impl Drop for TempCopy {
+    fn drop(&mut self) {
+        if let Err(e) = fs::remove_dir_all(&self.dir) {
+            debug!("failed to remove {}: {}", self.dir.display(), e);
+        }
+    }
+}
+
+/// The loaded ASUS DLL together with its initialized RPC client.
+///
+/// Dropping this uninitializes the RPC client before the library is unloaded,
+/// and removes the temp copy of the DLL, if one was needed, after that.
+struct Dll {
     lib: Library,
+    symbols: Symbols,
     client: *mut c_void,
+    // Declared after `lib` so the library is unloaded before the file is removed
+    _temp_copy: Option<TempCopy>,
 }
 
 // Safety: The client pointer is only used with the DLL functions
-// and the Library keeps the DLL loaded for the lifetime of AsusController
-unsafe impl Send for AsusController {}
-unsafe impl Sync for AsusController {}
+// and the Library keeps the DLL loaded for the lifetime of the Dll
+unsafe impl Send for Dll {}
+unsafe impl Sync for Dll {}
+
+impl Dll {
+    fn load(config: &ControllerConfig) -> Result<Self, ControllerError> {
+        let path = with_retries(config.package_retries, || {
+            let full_name = find_asus_package(&config.package_families())?;
+            get_package_path(&full_name)
+        })?;
+        let dll_path = format!("{}\\ModuleDll\\HWSettings\\{}", path, LOCAL_DLL_NAME);
+
+        // Prefer loading in place; copy only if the package path can't be loaded
+        // Declared before `lib` so an early return unloads the library first
+        let mut temp_copy = None;
+        let lib = match unsafe { Library::new(&dll_path) } {
+            Ok(lib) => lib,
+            Err(e) => {
+                debug!(
+                    "loading {} directly failed ({}), using a temp copy",
+                    dll_path, e
+                );
+                let (copy, target) = TempCopy::create(&dll_path)?;
+                temp_copy = Some(copy);
+                unsafe { Library::new(&target)? }
+            }
+        };
+
+        unsafe {
+            type InitFn = unsafe extern "C" fn(*mut *mut c_void) -> i64;
+            let init: Symbol<InitFn> = lib.get(b"MyOptRpcClientInitialize")?;
+
+            let mut client: *mut c_void = std::ptr::null_mut();
+            let result = init(&mut client);
+            if result != 0 || client.is_null() {
+                return Err(ControllerError::RpcInitFailed);
+            }
+
+            type CallbackFn = unsafe extern "C" fn(i32, i32, *const i8);
+            type SetCallbackFn = unsafe extern "C" fn(CallbackFn, *mut c_void);
+            let set_callback: Symbol<SetCallbackFn> =
+                lib.get(b"SetCallbackForReturnOptimizationResult")?;
+            set_callback(callback_state::mode_callback, client);
+
+            let symbols = Symbols::resolve(&lib);
+            Ok(Self {
+                lib,
+                symbols,
+                client,
+                _temp_copy: temp_copy,
+            })
+        }
+    }
+
+    /// Look up a function pointer, using the cached one if it was resolved at load.
+    fn resolve<T: Copy>(
+        &self,
+        cached: impl FnOnce(&Symbols) -> Option<T>,
+        symbol: &[u8],
+    ) -> Result<T, ControllerError> {
+        match cached(&self.symbols) {
+            Some(f) => Ok(f),
+            None => Ok(unsafe { *self.lib.get::<T>(symbol)? }),
+        }
+    }
+
+    fn getter(&self, symbol: &[u8]) -> Result<GetFn, ControllerError> {
+        self.resolve(|s| find(&s.getters, symbol), symbol)
+    }
+
+    fn setter(&self, symbol: &[u8]) -> Result<SetModeFn, ControllerError> {
+        self.resolve(|s| find(&s.setters, symbol), symbol)
+    }
+
+    /// Whether the DLL exports `symbol`, looking it up only if it isn't a known one.
+    fn has_symbol(&self, symbol: &[u8]) -> bool {
+        self.symbols.contains(symbol).unwrap_or_else(|| {
+            // Only resolved, never called, so the signature doesn't matter
+            unsafe { self.lib.get::<unsafe extern "C" fn()>(symbol) }.is_ok()
+        })
+    }
+}
+
+fn find<T: Copy>(symbols: &[(&[u8], T)], symbol: &[u8]) -> Option<T> {
+    symbols
+        .iter()
+        .find(|(name, _)| *name == symbol)
+        .map(|&(_, f)| f)
+}
+
+impl Drop for Dll {
+    fn drop(&mut self) {
+        unsafe {
+            type UninitFn = unsafe extern "C" fn(*mut c_void);
+            if let Ok(uninit) = self.lib.get::<UninitFn>(b"MyOptRpcClientUninitialize") {
+                uninit(self.client);
+            }
+        }
+    }
+}
+
+impl AsusController {
+    /// Create a new controller instance.
+    ///
+    /// Only one instance can exist at a time due to DLL/RPC limitations.
+    /// The instance guard is released when the controller is dropped.
+    ///
+    /// # Errors
+    ///
+    /// - [`ControllerError::AlreadyInitialized`] if another instance already exists
+    /// - [`ControllerError::PackageNotFound`] if the ASUS package is not installed
+    /// - [`ControllerError::IncompleteDllCopy`] if the DLL was not copied completely
+    /// - [`ControllerError::DllLoad`] if the DLL fails to load
+    /// - [`ControllerError::RpcInitFailed`] if RPC initialization fails
+    pub fn new() -> Result<Self, ControllerError> {
+        Self::builder().build()
+    }
+
+    /// Create a builder to configure the controller before initialization.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use azizo_core::AsusController;
+    ///
+    /// let controller = AsusController::builder().package_retries(5).build()?;
+    /// # Ok::<(), azizo_core::ControllerError>(())
+    /// ```
+    pub fn builder() -> AsusControllerBuilder {
+        AsusControllerBuilder::new()
+    }
+
+    /// Create a controller that looks for the DLL in the given package family first.
+    ///
+    /// Shorthand for `AsusController::builder().package_family(name).build()`;
+    /// see [`AsusControllerBuilder::package_family`].
+    pub fn with_package_family(name: &str) -> Result<Self, ControllerError> {
+        Self::builder().package_family(name).build()
+    }
+
+    fn with_config(config: ControllerConfig) -> Result<Self, ControllerError> {
+        if INSTANCE_EXISTS.swap(true, Ordering::SeqCst) {
+            return Err(ControllerError::AlreadyInitialized);
+        }
+
+        match Self::init_internal(config) {
+            Ok(controller) => Ok(controller),
+            Err(e) => {
+                INSTANCE_EXISTS.store(false, Ordering::SeqCst);
+                Err(e)
+            }
+        }
+    }
+
+    fn init_internal(config: ControllerConfig) -> Result<Self, ControllerError> {
+        let dll = Dll::load(&config)?;
+        let owner = system::OwnerRecord::claim().map(|record| Box::new(record) as Box<_>);
+        Ok(Self::from_parts(
+            config,
+            dll,
+            owner,
+            ddc::set_external_brightness,
+        ))
+    }
+
+    fn from_parts(
+        config: ControllerConfig,
+        dll: Dll,
+        owner: Option<Box<dyn Send + Sync>>,
+        external_brightness: fn(u32) -> usize,
+    ) -> Self {
+        Self {
+            shared: Arc::new(SharedDll {
+                dll: RwLock::new(Some(dll)),
+                _owner: owner,
+            }),
+            config,
+            temporary: Mutex::new(TemporaryMode::default()),
+            coalesced: Mutex::new(CoalescedMode::default()),
+            dimming_layers: Mutex::new(DimmingLayers::default()),
+            tasks: TaskRegistry::new(),
+            automation: AutomationGuard::new(),
+            error_hook: RwLock::new(None),
+            value_mapper: RwLock::new(None),
+            mirror_external: AtomicBool::new(false),
+            external_brightness,
+            power_override: AtomicBool::new(false),
+        }
+    }
+
+    /// Get another handle to this controller.
+    ///
+    /// The handle shares the loaded DLL and RPC client, so it doesn't count
+    /// against the one-instance limit and never fails with
+    /// [`ControllerError::AlreadyInitialized`]. The library stays loaded until
+    /// the last handle is dropped, and [`reload_library`](Self::reload_library)
+    /// on any handle reloads it for all of them.
+    ///
+    /// Background tasks and the [`automation_guard`](Self::automation_guard)
+    /// are shared too. The error hook, value mapper, temporary mode and
+    /// dimming overrides are per handle and start out empty.
+    pub fn clone_handle(&self) -> AsusControllerHandle {
+        Self {
+            shared: Arc::clone(&self.shared),
+            config: self.config.clone(),
+            temporary: Mutex::new(TemporaryMode::default()),
+            coalesced: Mutex::new(CoalescedMode::default()),
+            dimming_layers: Mutex::new(DimmingLayers::default()),
+            tasks: self.tasks.clone(),
+            automation: self.automation.clone(),
+            error_hook: RwLock::new(None),
+            value_mapper: RwLock::new(None),
+            mirror_external: AtomicBool::new(self.mirror_external.load(Ordering::SeqCst)),
+            external_brightness: self.external_brightness,
+            power_override: AtomicBool::new(false),
+        }
+    }
+
+    /// Reload the ASUS DLL without dropping the controller.
+    ///
+    /// Uninitializes the current RPC client, unloads the library, then copies
+    /// and loads the DLL again from the package path, re-initializing RPC and
+    /// re-registering the callback. Use this to pick up an updated DLL after an
+    /// ASUS update without restarting the application.
+    ///
+    /// The cached state is preserved across the reload. The library is shared
+    /// with any [`clone_handle`](Self::clone_handle) handles, which see the
+    /// reloaded one as well.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`AsusController::new`]. If the reload fails,
+    /// the controller is left without a library and operations return
+    /// [`ControllerError::LibraryNotLoaded`] until a later reload succeeds.
+    pub fn reload_library(&self) -> Result<(), ControllerError> {
+        let mut dll = self.shared.dll.write().unwrap();
+        // The old library must be released before the DLL file can be replaced
+        drop(dll.take());
+        info!("reloading ASUS DLL");
+        *dll = Some(Dll::load(&self.config)?);
+        Ok(())
+    }
+
+    /// Clear the cached state back to its uninitialized values.
+    ///
+    /// Mode and dimming go back to the `-1` sentinel and sliders to their
+    /// defaults, so the next [`sync_all_sliders`](DisplayController::sync_all_sliders)
+    /// behaves like a fresh start. Useful after [`reload_library`](Self::reload_library)
+    /// to avoid mixing data from before and after the reload.
+    pub fn reset_cache(&self) {
+        callback_state::reset();
+    }
+
+    /// Redirect stdout for the current scope if [`AsusControllerBuilder::suppress_dll_output`] is set.
+    fn suppress_stdout(&self) -> Option<system::StdoutSuppression> {
+        self.config
+            .suppress_dll_output
+            .then(system::StdoutSuppression::start)
+    }
+
+    fn with_dll<R>(
+        &self,
+        f: impl FnOnce(&Dll) -> Result<R, ControllerError>,
+    ) -> Result<R, ControllerError> {
+        let dll = self.shared.dll.read().unwrap();
+        match dll.as_ref() {
+            Some(dll) => f(dll),
+            None => Err(ControllerError::LibraryNotLoaded),
+        }
+    }
+
+    /// Check that the loaded DLL exports every symbol a mode needs.
+    ///
+    /// See [`DisplayMode::required_symbols`]. Called by
+    /// [`set_mode`](DisplayController::set_mode) before applying.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ControllerError::SymbolNotFound`] naming the first missing symbol.
+    pub fn check_symbols(&self, mode: &dyn DisplayMode) -> Result<(), ControllerError> {
+        self.with_dll(|dll| {
+            for &symbol in mode.required_symbols() {
+                if !dll.has_symbol(symbol) {
+                    return Err(ControllerError::SymbolNotFound(
+                        String::from_utf8_lossy(symbol).into_owned(),
+                    ));
+                }
+            }
+            Ok(())
+        })
+    }
+
+    fn call_rpc_get(&self, symbol: &[u8]) -> Result<i64, ControllerError> {
+        self.with_dll(|dll| unsafe {
+            let func = dll.getter(symbol)?;
+            let result = func(dll.client);
+            session_log::record(format_args!(
+                "get {} -> {}",
+                String::from_utf8_lossy(symbol),
+                result
+            ));
+            Ok(result)
+        })
+    }
+
+    /// Request values from the DLL and wait for the callbacks that carry them.
+    ///
+    /// Each request is a getter symbol and the callback code it is answered
+    /// with. All requests are sent before waiting, sharing one timeout.
+    fn request_and_wait(&self, requests: &[(&[u8], i32)]) -> Result<(), ControllerError> {
+        self.request_and_wait_for(requests, self.config.callback_timeout)
+    }
+
+    /// [`request_and_wait`](Self::request_and_wait) with an explicit timeout.
+    fn request_and_wait_for(
+        &self,
+        requests: &[(&[u8], i32)],
+        timeout: Duration,
+    ) -> Result<(), ControllerError> {
+        // Covers the requests and the replies, which the DLL may log as they arrive
+        let _quiet = self.suppress_stdout();
+        let since: Vec<u64> = requests
+            .iter()
+            .map(|&(_, func)| callback_state::received(func))
+            .collect();
+        for &(symbol, _) in requests {
+            self.call_rpc_get(symbol)?;
+        }
+
+        let deadline = std::time::Instant::now() + timeout;
+        for (&(symbol, func), since) in requests.iter().zip(since) {
+            let remaining = deadline.saturating_duration_since(std::time::Instant::now());
+            if !callback_state::wait_for(func, since, remaining) {
+                warn!(
+                    "no callback for {} within {:?}",
+                    String::from_utf8_lossy(symbol),
+                    timeout
+                );
+                return Err(ControllerError::CallbackTimeout(timeout));
+            }
+        }
+        Ok(())
+    }
+
+    /// Set a splendid mode with a value parameter.
+    ///
+    /// This is used internally by mode implementations.
+    pub fn set_splendid_mode(&self, symbol: &[u8], value: u8) -> Result<(), ControllerError> {
+        let value = match symbol {
+            b"MyOptSetSplendidManualFunc" => self.map_value(ModeKind::Manual, value),
+            b"MyOptSetSplendidEyecareFunc" => self.map_value(ModeKind::EyeCare, value),
+            // Other symbols take a mode ID rather than a parameter
+            _ => value,
+        };
+        self.with_dll(|dll| unsafe {
+            let set_fn = dll.setter(symbol)?;
+            let result = set_fn(value, c"".as_ptr(), dll.client);
+            session_log::record(format_args!(
+                "set {} value={} -> {}",
+                String::from_utf8_lossy(symbol),
+                value,
+                result
+            ));
+            Ok(())
+        })
+    }
+
+    /// Set monochrome/e-reading mode with grayscale and temp.
+    ///
+    /// This is used internally by [`EReadingMode`].
+    /// Temperature is -50 to +50 (0 is neutral).
+    pub fn set_monochrome_mode(&self, grayscale: u8, temp: i8) -> Result<(), ControllerError> {
+        let grayscale = self.map_value(ModeKind::EReading, grayscale);
+        self.with_dll(|dll| unsafe {
+            let set_mono = dll.resolve(|s| s.set_monochrome, b"MyOptSetSplendidMonochromeFunc")?;
+            let value = Self::encode_monochrome(grayscale, temp);
+            debug!(
+                "sending monochrome value {} (grayscale={}, temp={})",
+                value, grayscale, temp
+            );
+            let result = set_mono(value, dll.client);
+            session_log::record(format_args!(
+                "set monochrome grayscale={}, temp={}, value={} -> {}",
+                grayscale, temp, value, result
+            ));
+            Ok(())
+        })
+    }
+
+    /// Compute the raw value sent to the DLL for monochrome/e-reading mode.
+    ///
+    /// The grayscale and temperature are packed as `grayscale * 256 + temp - 206`.
+    pub fn encode_monochrome(grayscale: u8, temp: i8) -> i32 {
+        (grayscale as i32 * 256) + temp as i32 - 206
+    }
+
+    /// Split a monochrome value reported by the DLL into `(grayscale, temp)`.
+    ///
+    /// The inverse of [`encode_monochrome`](Self::encode_monochrome). Each
+    /// grayscale owns the 256 values centred on its neutral temperature, so
+    /// negative temperatures decode to the same grayscale as positive ones.
+    pub(crate) fn decode_monochrome(value: i32) -> (i32, i32) {
+        let raw = value + 206 + 128;
+        (raw.div_euclid(256), raw.rem_euclid(256) - 128)
+    }
+
+    /// Enable e-reading mode with specific grayscale and temperature.
+    ///
+    /// Unlike [`toggle_e_reading`](DisplayController::toggle_e_reading), this never
+    /// switches e-reading off. The current mode is recorded as the mode to restore
+    /// when e-reading is later toggled off.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ControllerError::InvalidSliderValue`] if the grayscale is out of
+    /// range and [`ControllerError::InvalidTemperature`] if the temperature is.
+    pub fn enable_e_reading(&self, grayscale: u8, temp: i8) -> Result<(), ControllerError> {
+        let mode = EReadingMode::new(grayscale, temp)?;
+
+        let state = self.get_state();
+        if !state.is_monochrome && state.mode_id > 0 {
+            callback_state::store_last_non_ereading_mode(state.mode_id);
+        }
+
+        info!("enabling e-reading: grayscale={}, temp={}", grayscale, temp);
+        self.set_mode(&mode)
+    }
+
+    /// Set a hook invoked whenever a background operation fails.
+    ///
+    /// Background work such as the [`apply_temporary`](Self::apply_temporary)
+    /// timer or a [`SystemThemeWatcher`](crate::SystemThemeWatcher) has no caller
+    /// to return an error to, so its failures are logged and passed to this hook,
+    /// letting the app surface them in its UI. Foreground calls still return
+    /// their `Result` as before. Replaces any previously set hook.
+    pub fn set_error_hook(&self, hook: ErrorHook) {
+        *self.error_hook.write().unwrap() = Some(hook);
+    }
+
+    /// Register a hook called whenever e-reading is switched on or off.
+    ///
+    /// Fires from the device's mode callback when its monochrome flag flips,
+    /// whether the change came from this app or elsewhere, e.g. the ASUS
+    /// hotkey. Cheaper than [`subscribe`](Self::subscribe) when only e-reading
+    /// matters. Hooks run on the DLL's callback thread, so they should return
+    /// quickly. Hooks stay registered until the controller is dropped.
+    pub fn on_e_reading_change(&self, hook: EReadingHook) {
+        callback_state::on_e_reading_change(hook);
+    }
+
+    /// Set a transform applied to mode values just before they are sent to the DLL.
+    ///
+    /// Lets users with miscalibrated panels remap values, e.g. send eye care
+    /// level 2 when level 3 is requested. The mapper receives the Manual slider
+    /// value, the Eye Care level or the E-Reading grayscale, with the mode's kind;
+    /// Normal and Vivid have no value to map. Its output is sent unchecked. The
+    /// cached state reports whatever the DLL echoes back. Replaces any previously
+    /// set mapper; without one, values are sent unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use azizo_core::{AsusController, ModeKind};
+    ///
+    /// let controller = AsusController::new()?;
+    /// controller.set_value_mapper(Box::new(|kind, value| match (kind, value) {
+    ///     (ModeKind::EyeCare, 3) => 2,
+    ///     _ => value,
+    /// }));
+    /// # Ok::<(), azizo_core::ControllerError>(())
+    /// ```
+    pub fn set_value_mapper(&self, mapper: ValueMapper) {
+        *self.value_mapper.write().unwrap() = Some(mapper);
+    }
+
+    fn map_value(&self, kind: ModeKind, value: u8) -> u8 {
+        let Some(mapped) = self
+            .value_mapper
+            .read()
+            .unwrap()
+            .as_ref()
+            .map(|m| m(kind, value))
+        else {
+            return value;
+        };
+        if mapped != value {
+            debug!(
+                "value mapper changed {:?} value {} to {}",
+                kind, value, mapped
+            );
+        }
+        mapped
+    }
+
+    /// Mirror dimming changes to external monitors over DDC/CI.
+    ///
+    /// External displays are not controlled by Splendid, but most support
+    /// brightness over DDC/CI. While enabled, every dimming change, whether from
+    /// [`set_dimming`](DisplayController::set_dimming), a percentage, an
+    /// override, a fade or nits, is also applied as a percentage to every
+    /// DDC/CI-capable monitor, scaled to its brightness range. Monitors without
+    /// DDC/CI are skipped, so this is a no-op if none is found.
+    pub fn set_mirror_external(&self, enabled: bool) {
+        self.mirror_external.store(enabled, Ordering::SeqCst);
+    }
+
+    /// Apply a splendid dimming level to external monitors, if mirroring is on.
+    ///
+    /// Called by [`set_dimming`](DisplayController::set_dimming), which every
+    /// other dimming setter goes through.
+    fn mirror_dimming(&self, level: i32) {
+        if self.mirror_external.load(Ordering::SeqCst) {
+            let percent = Self::dimming_to_percent(level);
+            let updated = (self.external_brightness)(percent as u32);
+            debug!(
+                "mirrored dimming {}% to {} external monitor(s)",
+                percent, updated
+            );
+        }
+    }
+
+    /// Append every callback and DLL get/set call to a trace file.
+    ///
+    /// Each line is timestamped (seconds since the Unix epoch). This works
+    /// independently of the `log` crate, so users can capture a self-contained
+    /// trace for bug reports. Once the file exceeds 1 MiB it is rotated to
+    /// `<path>.1`, keeping at most two files.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ControllerError::Io`] if the file cannot be opened.
+    pub fn enable_callback_logging(&self, path: PathBuf) -> Result<(), ControllerError> {
+        session_log::enable(path)?;
+        Ok(())
+    }
+
+    /// Stop writing the trace file started by
+    /// [`enable_callback_logging`](Self::enable_callback_logging).
+    pub fn disable_callback_logging(&self) {
+        session_log::disable();
+    }
+
+    /// Start recording a timeline of state changes reported by the device.
+    ///
+    /// Each callback that changes the state adds an entry to
+    /// [`session_history`](Self::session_history). Only the newest `limit`
+    /// entries are kept. Calling this again starts a new, empty timeline.
+    pub fn enable_session_history(&self, limit: usize) {
+        callback_state::enable_history(limit);
+    }
+
+    /// Stop recording and discard the timeline.
+    pub fn disable_session_history(&self) {
+        callback_state::disable_history();
+    }
+
+    /// State changes recorded since
+    /// [`enable_session_history`](Self::enable_session_history), oldest first.
+    ///
+    /// Each [`StateDiff`] holds `(before, after)` pairs for the fields that
+    /// changed. Empty if recording is off.
+    pub fn session_history(&self) -> Vec<(std::time::Instant, StateDiff)> {
+        callback_state::history()
+    }
+
+    /// Receive a state snapshot after every device callback.
+    ///
+    /// This includes changes made by ASUS's own software, which shares the
+    /// callback. Any number of subscribers may exist; each gets its own
+    /// channel, and it is unregistered once its receiver is dropped.
+    ///
+    /// A snapshot is taken when it is sent, so rapid updates may coalesce:
+    /// one snapshot can already include later changes, and consecutive
+    /// snapshots may be identical.
+    pub fn subscribe(&self) -> Receiver<ControllerState> {
+        let (sender, receiver) = mpsc::channel();
+        callback_state::subscribe(sender);
+        receiver
+    }
+
+    /// Apply a named e-reading preset.
+    ///
+    /// See [`EReadingPreset`] for the grayscale and temperature of each preset.
+    pub fn apply_ereading_preset(&self, preset: EReadingPreset) -> Result<(), ControllerError> {
+        self.set_mode(&preset.to_mode())
+    }
+
+    /// Build and apply a mode from its kind and an optional parameter.
+    ///
+    /// This is a flat alternative to constructing mode structs, convenient for
+    /// CLIs and config-driven apps. Missing parameters fall back to the cached
+    /// slider values; see [`ModeKind::to_mode`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ControllerError::InvalidSliderValue`] if `param` is out of range
+    /// for `kind`.
+    pub fn set_mode_kind(&self, kind: ModeKind, param: Option<u8>) -> Result<(), ControllerError> {
+        let mode = kind.to_mode(param, &self.get_state())?;
+        self.set_mode(&*mode)
+    }
+
+    /// Apply Eye Care mode at its strongest blue light filter level.
+    pub fn max_eyecare(&self) -> Result<(), ControllerError> {
+        self.set_mode(&EyeCareMode::max())
+    }
+
+    /// Apply Eye Care mode at its weakest blue light filter level.
+    pub fn min_eyecare(&self) -> Result<(), ControllerError> {
+        self.set_mode(&EyeCareMode::min())
+    }
+
+    /// Apply the dimming (0-100) for the current power source.
+    ///
+    /// On battery, `battery_percent` is pushed as a dimming override (see
+    /// [`push_dimming_override`](DisplayController::push_dimming_override)). On AC
+    /// power, that override is popped again, restoring the user's base dimming;
+    /// `ac_percent` becomes the base only if none is known yet. Repeated calls for
+    /// the same power source don't stack overrides. Does nothing if the power
+    /// source cannot be determined.
+    ///
+    /// A one-off check; use [`PowerWatcher`](crate::PowerWatcher) to follow power
+    /// changes with debouncing.
+    pub fn on_power_change(
+        &self,
+        ac_percent: i32,
+        battery_percent: i32,
+    ) -> Result<(), ControllerError> {
+        match system::on_ac_power() {
+            Some(true) if self.power_override.swap(false, Ordering::SeqCst) => {
+                self.pop_dimming_override()
+            }
+            Some(true) if self.base_dimming_percent().is_none() => {
+                self.set_base_dimming_percent(ac_percent)
+            }
+            Some(false) if !self.power_override.swap(true, Ordering::SeqCst) => {
+                self.push_dimming_override(battery_percent)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Apply a mode, then clamp the current dimming into `dimming_bounds`.
+    ///
+    /// The bounds are inclusive percentages (0-100), e.g. `0..=70` to keep
+    /// e-reading from getting too bright or `100..=100` for movies. Dimming is
+    /// only written if it falls outside the bounds, and left alone while it is
+    /// still unknown.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error from [`set_mode`](DisplayController::set_mode) or
+    /// [`set_dimming`](DisplayController::set_dimming).
+    pub fn set_mode_constrained(
+        &self,
+        mode: &dyn DisplayMode,
+        dimming_bounds: RangeInclusive<i32>,
+    ) -> Result<(), ControllerError> {
+        self.set_mode(mode)?;
+
+        let current = self.get_state().dimming;
+        if current < 0 {
+            debug!("dimming unknown, not applying {:?}", dimming_bounds);
+            return Ok(());
+        }
+        let (min, max) = Self::splendid_bounds(&dimming_bounds).into_inner();
+        let clamped = current.clamp(min, max);
+        if clamped != current {
+            debug!("clamping dimming {} into {}..={}", current, min, max);
+            self.set_dimming(clamped)?;
+        }
+        Ok(())
+    }
+
+    /// Convert inclusive percentage bounds (0-100) to splendid units (40-100).
+    ///
+    /// Clamping in splendid units keeps the result inside the bounds, which
+    /// rounding a clamped percentage to a 10% step would not.
+    pub(crate) fn splendid_bounds(bounds: &RangeInclusive<i32>) -> RangeInclusive<i32> {
+        let min = Self::percent_to_dimming((*bounds.start()).clamp(0, 100));
+        let max = Self::percent_to_dimming((*bounds.end()).clamp(0, 100)).max(min);
+        min..=max
+    }
+
+    /// Set dimming and check that the panel actually applied it.
+    ///
+    /// Some panels silently cap or ignore certain dimming values while the DLL
+    /// still reports success. After setting, the mode is re-queried, whose
+    /// callback carries the dimming, and the reported value is compared to the
+    /// requested one (clamped to 40-100).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ControllerError::DimmingMismatch`] if the reported dimming
+    /// differs by more than `tolerance` splendid units, and
+    /// [`ControllerError::CallbackTimeout`] if no dimming is reported.
+    pub fn set_dimming_verified(&self, level: i32, tolerance: i32) -> Result<(), ControllerError> {
+        let requested = level.clamp(40, 100);
+        self.set_dimming(requested)?;
+        self.request_and_wait(&[(b"MyOptGetSplendidColorModeFunc", callback_state::MODE)])?;
+
+        let reported = self.get_state().dimming;
+        if (reported - requested).abs() > tolerance {
+            warn!("dimming {} requested but {} reported", requested, reported);
+            return Err(ControllerError::DimmingMismatch {
+                requested,
+                reported,
+            });
+        }
+        Ok(())
+    }
+
+    /// Flip dimming between two presets, e.g. 100% for work and 50% for reading.
+    ///
+    /// Applies whichever preset the current dimming is further from, so repeated
+    /// calls alternate between the two. Presets are rounded to the 10% steps
+    /// [`set_dimming_percent`](DisplayController::set_dimming_percent) applies.
+    /// Returns the applied percentage.
+    pub fn toggle_dimming(&self, a_percent: i32, b_percent: i32) -> Result<i32, ControllerError> {
+        let a_percent = Self::dimming_step(a_percent);
+        let b_percent = Self::dimming_step(b_percent);
+        let current = self.dimming_percent();
+        let target = if (current - a_percent).abs() <= (current - b_percent).abs() {
+            b_percent
+        } else {
+            a_percent
+        };
+        debug!("toggling dimming from {}% to {}%", current, target);
+        self.set_dimming_percent(target)?;
+        Ok(target)
+    }
+
+    /// Round a percentage to the 10% steps used by
+    /// [`set_dimming_percent`](DisplayController::set_dimming_percent), clamped to 0-100.
+    fn dimming_step(percent: i32) -> i32 {
+        ((percent as f32 / 10.0).round() as i32 * 10).clamp(0, 100)
+    }
+
+    /// Set dimming to the splendid value closest to a target luminance in nits.
+    ///
+    /// Splendid does not report the panel's luminance, so this assumes brightness
+    /// scales linearly with the splendid value, reaching the configured max
+    /// luminance at 100 (see [`AsusControllerBuilder::max_luminance`]). The result
+    /// is approximate; targets outside the dimmable range are clamped.
+    pub fn set_dimming_nits(&self, nits: f32) -> Result<(), ControllerError> {
+        self.set_dimming(Self::nits_to_dimming(nits, self.config.max_luminance))
+    }
+
+    /// Check whether Splendid responds to requests.
+    ///
+    /// Splendid can be disabled in Armoury Crate, in which case RPC
+    /// initialization still succeeds but mode changes have no effect. There is no
+    /// documented flag for this, so the current mode is requested and this
+    /// returns whether the DLL reported it back within the callback timeout
+    /// (see [`AsusControllerBuilder::callback_timeout`]).
+    pub fn is_splendid_enabled(&self) -> Result<bool, ControllerError> {
+        match self.request_and_wait(&[(b"MyOptGetSplendidColorModeFunc", callback_state::MODE)]) {
+            Ok(()) => Ok(true),
+            Err(ControllerError::CallbackTimeout(_)) => {
+                warn!("no mode reported by Splendid, it may be disabled");
+                Ok(false)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// List running ASUS utilities known to override Splendid settings.
+    ///
+    /// If Armoury Crate or MyASUS is open, it may send its own Splendid commands
+    /// and revert changes made through this controller. Returns the display names
+    /// of the conflicting applications that are currently running.
+    pub fn detect_conflicting_apps(&self) -> Vec<String> {
+        let running = system::running_process_names();
+        system::CONFLICTING_APPS
+            .iter()
+            .filter(|(exe, _)| running.iter().any(|name| name.eq_ignore_ascii_case(exe)))
+            .map(|(_, name)| name.to_string())
+            .collect()
+    }
+
+    /// Apply a mode and wait until the controller state reports it.
+    ///
+    /// After applying, the cached state (updated by DLL callbacks) is polled
+    /// until its mode ID and monochrome flag match `mode`. For e-reading only the
+    /// monochrome flag is checked, since it has no mode ID of its own.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ControllerError::VerifyTimeout`] if the state does not match
+    /// within `timeout`, plus any error from applying the mode.
+    pub fn apply_verified(
+        &self,
+        mode: &dyn DisplayMode,
+        timeout: Duration,
+    ) -> Result<(), ControllerError> {
+        self.set_mode(mode)?;
+
+        let deadline = std::time::Instant::now() + timeout;
+        loop {
+            let state = self.get_state();
+            let matches = state.is_monochrome == mode.is_ereading()
+                && (mode.is_ereading() || state.mode_id == mode.mode_id());
+            if matches {
+                return Ok(());
+            }
+            if std::time::Instant::now() >= deadline {
+                warn!("{} not confirmed within {:?}", mode.describe(), timeout);
+                return Err(ControllerError::VerifyTimeout(timeout));
+            }
+            std::thread::sleep(Duration::from_millis(50));
+        }
+    }
+
+    /// Run `f`, then restore the mode and dimming from before the call.
+    ///
+    /// For "try it and put it back" workflows such as probing the hardware. The
+    /// state is restored whether or not `f` succeeds. An error from `f` takes
+    /// precedence over an error while restoring.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use azizo_core::{AsusController, DisplayController, VividMode};
+    ///
+    /// let controller = AsusController::new()?;
+    /// let supported = controller.with_restored_state(|c| {
+    ///     c.set_mode(&VividMode::new())?;
+    ///     Ok(c.get_state().mode_id == 2)
+    /// })?;
+    /// # Ok::<(), azizo_core::ControllerError>(())
+    /// ```
+    pub fn with_restored_state<F, R>(&self, f: F) -> Result<R, ControllerError>
+    where
+        F: FnOnce(&Self) -> Result<R, ControllerError>,
+    {
+        let saved = self.get_state();
+        let result = f(self);
+        let restored = self.restore_state(&saved);
+        let value = result?;
+        restored?;
+        Ok(value)
+    }
+
+    /// Cycle through every standard mode, pausing `dwell` on each, then restore
+    /// the original mode and dimming.
+    ///
+    /// Shows Normal, Vivid, each Eye Care level and E-Reading (with the cached
+    /// grayscale and temperature). Useful for demos, screenshots and manual QA.
+    /// Blocks for the whole cycle. The original settings are restored even if a
+    /// step fails.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error from applying a step, or else from restoring.
+    pub fn demo_cycle(&self, dwell: Duration) -> Result<(), ControllerError> {
+        self.with_restored_state(|controller| {
+            let state = controller.get_state();
+            let mut steps: Vec<Box<dyn DisplayMode>> =
+                vec![Box::new(NormalMode::new()), Box::new(VividMode::new())];
+            for level in EyeCareMode::MIN_LEVEL..=EyeCareMode::MAX_LEVEL {
+                steps.push(Box::new(EyeCareMode::new(level)?));
+            }
+            steps.push(Box::new(EReadingMode::from_controller_state(&state)));
+
+            for mode in steps {
+                info!("demo: {}", mode.describe());
+                controller.set_mode(&*mode)?;
+                std::thread::sleep(dwell);
+            }
+            Ok(())
+        })
+    }
+
+    /// Apply a mode for a fixed duration, then restore the previous mode and dimming.
+    ///
+    /// A background timer thread holds a handle to the controller, so the
+    /// previous settings are restored even if the caller drops its own handle.
+    /// Calling this again while a temporary mode is active replaces the mode and
+    /// restarts the timer; the settings from before the first call are restored.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use azizo_core::{AsusController, EyeCareMode};
+    /// use std::sync::Arc;
+    /// use std::time::Duration;
+    ///
+    /// let controller = Arc::new(AsusController::new()?);
+    /// // Warm mode for a 20-minute reading session
+    /// controller.apply_temporary(&EyeCareMode::max(), Duration::from_secs(20 * 60))?;
+    /// # Ok::<(), azizo_core::ControllerError>(())
+    /// ```
+    pub fn apply_temporary(
+        self: &Arc<Self>,
+        mode: &dyn DisplayMode,
+        duration: Duration,
+    ) -> Result<(), ControllerError> {
+        let generation = {
+            let mut temporary = self.temporary.lock().unwrap();
+            if temporary.restore.is_none() {
+                temporary.restore = Some(self.get_state());
+            }
+            temporary.generation += 1;
+            temporary.generation
+        };
+
+        if let Err(e) = self.set_mode(mode) {
+            // Don't leave a previous temporary mode active without a timer
+            let (restore, task) = {
+                let mut temporary = self.temporary.lock().unwrap();
+                (temporary.restore.take(), temporary.task.take())
+            };
+            if let Some(superseded) = task {
+                self.tasks.stop(superseded);
+            }
+            if let Some(state) = restore {
+                let _ = self.restore_state(&state);
+            }
+            return Err(e);
+        }
+        info!("applied {} for {:?}", mode.describe(), duration);
+
+        // Stopping or superseding the timer sends on `cancel`, ending the wait
+        let (cancel, cancelled) = mpsc::channel::<()>();
+        let stop = Arc::new(AtomicBool::new(false));
+        let task_id =
+            self.tasks
+                .register_with_waker(TaskKind::TemporaryMode, Arc::clone(&stop), move || {
+                    let _ = cancel.send(());
+                });
+        if let Some(superseded) = self.temporary.lock().unwrap().task.replace(task_id) {
+            self.tasks.stop(superseded);
+        }
+
+        // Hold only a weak reference while waiting, so the timer doesn't keep
+        // a dropped controller alive
+        let controller = Arc::downgrade(self);
+        std::thread::spawn(move || {
+            // Disconnected means the registry, and with it the controller, is gone
+            if let Err(mpsc::RecvTimeoutError::Disconnected) = cancelled.recv_timeout(duration) {
+                return;
+            }
+            let Some(controller) = controller.upgrade() else {
+                return;
+            };
+            controller.tasks.unregister(task_id);
+
+            let restore = {
+                let mut temporary = controller.temporary.lock().unwrap();
+                if temporary.generation != generation {
+                    return;
+                }
+                temporary.task = None;
+                temporary.restore.take()
+            };
+            if stop.load(Ordering::SeqCst) {
+                debug!("temporary mode timer stopped, keeping the current mode");
+                return;
+            }
+
+            if let Some(state) = restore {
+                info!("temporary mode expired, restoring previous settings");
+                if let Err(e) = controller.restore_state(&state) {
+                    controller.report_error(&e);
+                }
+            }
+        });
+
+        Ok(())
+    }
+
+    /// List the background automation tasks currently running.
+    ///
+    /// Includes temporary-mode timers, state locks and any watchers that were
+    /// given this controller's [`task_registry`](Self::task_registry).
+    pub fn active_tasks(&self) -> Vec<TaskInfo> {
+        self.tasks.active_tasks()
+    }
+
+    /// Stop a background task by its [`TaskInfo::id`], returning whether it was running.
+    ///
+    /// Stopping a temporary-mode timer cancels the restore, keeping the
+    /// temporary mode.
+    pub fn stop_task(&self, id: u64) -> bool {
+        self.tasks.stop(id)
+    }
+
+    /// The registry tracking this controller's background tasks.
+    ///
+    /// Pass it to watchers with [`WatcherOptions::tasks`](crate::WatcherOptions::tasks)
+    /// so they show up in [`active_tasks`](Self::active_tasks).
+    pub fn task_registry(&self) -> TaskRegistry {
+        self.tasks.clone()
+    }
+
+    /// The guard that [`claim_manual`](Self::claim_manual) pauses.
+    ///
+    /// Pass it to watchers with [`WatcherOptions::guard`](crate::WatcherOptions::guard)
+    /// so manual input holds their changes back.
+    pub fn automation_guard(&self) -> AutomationGuard {
+        self.automation.clone()
+    }
 
-impl AsusController {
-    /// Create a new controller instance.
+    /// Hold back automatic changes for `duration` after manual input.
     ///
-    /// Only one instance can exist at a time due to DLL/RPC limitations.
-    /// The instance guard is released when the controller is dropped.
+    /// Call this whenever the user adjusts something, e.g. while dragging a
+    /// slider, so a scheduler or watcher doesn't undo it. Each call restarts
+    /// the window; once it passes, the latest held-back change of each
+    /// automation is applied. Only automation routed through
+    /// [`automation_guard`](Self::automation_guard) is paused. See
+    /// [`AutomationGuard::pause_for`].
+    pub fn claim_manual(&self, duration: Duration) {
+        self.automation.pause_for(duration);
+    }
+
+    /// Request a mode change, coalescing it with other requests in a short window.
     ///
-    /// # Errors
+    /// For bursts such as rapid hotkeys or profile application: the mode is sent
+    /// once no newer request has arrived for the coalesce window (see
+    /// [`AsusControllerBuilder::mode_coalesce_window`]), so only the last of
+    /// several quick changes reaches the device and the display doesn't flicker.
+    /// The most recent request is always applied eventually.
     ///
-    /// - [`ControllerError::AlreadyInitialized`] if another instance already exists
-    /// - [`ControllerError::PackageNotFound`] if the ASUS package is not installed
-    /// - [`ControllerError::DllLoad`] if the DLL fails to load
-    /// - [`ControllerError::RpcInitFailed`] if RPC initialization fails
-    pub fn new() -> Result<Self, ControllerError> {
-        if INSTANCE_EXISTS.swap(true, Ordering::SeqCst) {
-            return Err(ControllerError::AlreadyInitialized);
-        }
+    /// This returns immediately. Failures are passed to
+    /// [`report_error`](DisplayController::report_error) and thus the error hook.
+    pub fn set_mode_coalesced(self: &Arc<Self>, mode: Box<dyn DisplayMode>) {
+        let generation = {
+            let mut coalesced = self.coalesced.lock().unwrap();
+            coalesced.generation += 1;
+            coalesced.pending = Some(mode);
+            coalesced.generation
+        };
 
-        match Self::init_internal() {
-            Ok(controller) => Ok(controller),
-            Err(e) => {
-                INSTANCE_EXISTS.store(false, Ordering::SeqCst);
-                Err(e)
+        let controller = Arc::clone(self);
+        std::thread::spawn(move || {
+            std::thread::sleep(controller.config.mode_coalesce_window);
+
+            let mode = {
+                let mut coalesced = controller.coalesced.lock().unwrap();
+                if coalesced.generation != generation {
+                    return;
+                }
+                coalesced.pending.take()
+            };
+
+            if let Some(mode) = mode {
+                debug!("applying coalesced {}", mode.describe());
+                if let Err(e) = controller.set_mode(&*mode) {
+                    controller.report_error(&e);
+                }
             }
+        });
+    }
+
+    /// Query the current mode from the device as a [`ModeSnapshot`].
+    ///
+    /// Unlike [`get_current_mode`](DisplayController::get_current_mode), an
+    /// unrecognized mode ID is returned as [`ModeSnapshot::Unknown`] rather than
+    /// an error.
+    pub fn mode_snapshot(&self) -> Result<ModeSnapshot, ControllerError> {
+        self.request_and_wait(&[(b"MyOptGetSplendidColorModeFunc", callback_state::MODE)])?;
+        Ok(ModeSnapshot::from_state(&self.get_state()))
+    }
+
+    /// Export the current mode and dimming as `azizo` CLI commands.
+    ///
+    /// For sharing a setup as copy-pasteable commands, e.g.
+    ///
+    /// ```text
+    /// azizo-cli set-mode eyecare 3
+    /// azizo-cli dimming 60
+    /// ```
+    ///
+    /// Uses the cached state; call
+    /// [`sync_all_sliders`](DisplayController::sync_all_sliders) first for fresh
+    /// values. A mode this crate does not recognize is written as a comment, and
+    /// the dimming is left out until it has been reported. For a
+    /// machine-readable export, serialize the
+    /// [`ControllerState`] or a [`Profile`](crate::Profile) with the `serde`
+    /// feature instead.
+    pub fn export_script(&self) -> String {
+        let state = self.get_state();
+        let mut script = match ModeSnapshot::from_state(&state) {
+            ModeSnapshot::Unknown(mode_id) => format!("# unknown mode {} not exported\n", mode_id),
+            mode => format!("azizo-cli set-mode {}\n", mode),
+        };
+        if state.dimming >= 40 {
+            script += &format!(
+                "azizo-cli dimming {}\n",
+                Self::dimming_to_percent(state.dimming)
+            );
         }
+        script
     }
 
-    fn init_internal() -> Result<Self, ControllerError> {
-        let full_name = find_asus_package()?;
-        let path = get_package_path(&full_name)?;
-        let dll_path = format!("{}\\ModuleDll\\HWSettings\\{}", path, LOCAL_DLL_NAME);
+    /// Apply a mode described by a [`ModeSnapshot`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ControllerError::UnknownMode`] for [`ModeSnapshot::Unknown`],
+    /// plus the errors of [`ModeSnapshot::to_mode`] and applying the mode.
+    pub fn apply_mode_snapshot(&self, snapshot: &ModeSnapshot) -> Result<(), ControllerError> {
+        self.set_mode(&*snapshot.to_mode()?)
+    }
 
-        fs::copy(&dll_path, LOCAL_DLL_NAME)?;
+    /// Switch back to the mode that was active before the last mode change.
+    ///
+    /// Every [`set_mode`](DisplayController::set_mode) that changes the mode (not
+    /// just a slider value) records the mode it replaced, including e-reading.
+    /// Restoring is itself a mode change, so calling this twice toggles between
+    /// the two most recent modes. Returns the mode that was applied.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ControllerError::ModeNotDetected`] if no mode change has been
+    /// recorded yet, plus the errors of applying the mode.
+    pub fn restore_previous_mode(&self) -> Result<Box<dyn DisplayMode>, ControllerError> {
+        let previous = callback_state::previous_mode().ok_or(ControllerError::ModeNotDetected)?;
+        let mode = previous.to_mode()?;
+        info!("restoring previous mode: {}", mode.describe());
+        self.set_mode(&*mode)?;
+        Ok(mode)
+    }
 
-        unsafe {
-            let lib = Library::new(LOCAL_DLL_NAME)?;
+    /// Get the mode, dimming and e-reading flag from one consistent snapshot.
+    ///
+    /// Unlike calling [`get_current_mode`](DisplayController::get_current_mode)
+    /// and [`get_state`](DisplayController::get_state) separately, there is no
+    /// window in which a callback can change one value but not the other. Uses
+    /// the cached state and does not query the device.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ControllerError::ModeNotDetected`] if the cached mode is unknown.
+    pub fn full_status(&self) -> Result<FullStatus, ControllerError> {
+        let state = self.get_state();
+        Ok(FullStatus {
+            mode: modes::mode_from_state(&state)?,
+            dimming_percent: Self::dimming_to_percent(state.dimming),
+            e_reading: state.is_monochrome,
+        })
+    }
 
-            type InitFn = unsafe extern "C" fn(*mut *mut c_void) -> i64;
-            let init: Symbol<InitFn> = lib.get(b"MyOptRpcClientInitialize")?;
+    /// Sync from the device and report how it differs from a `target` state.
+    ///
+    /// Meant for a watchdog that re-applies the user's settings when another
+    /// utility (such as Armoury Crate) overrides them. Returns `None` if the
+    /// device matches the target. See [`ControllerState::diff`].
+    pub fn drift_from(
+        &self,
+        target: &ControllerState,
+    ) -> Result<Option<StateDiff>, ControllerError> {
+        self.sync_all_sliders()?;
+        let diff = self.get_state().diff(target);
+        if let Some(diff) = &diff {
+            debug!("state drifted from target: {:?}", diff);
+        }
+        Ok(diff)
+    }
 
-            let mut client: *mut c_void = std::ptr::null_mut();
-            let result = init(&mut client);
-            if result != 0 || client.is_null() {
-                return Err(ControllerError::RpcInitFailed);
-            }
+    /// Keep the display at `target`, re-applying it whenever it drifts.
+    ///
+    /// A background thread checks the cached state every second and re-applies
+    /// the target mode and dimming when another utility overrides them. Drift
+    /// must persist for two consecutive checks first, so the lock doesn't fight
+    /// a user in the middle of an adjustment. Only the active mode (with its
+    /// parameters) and the dimming are enforced. See [`drift_from`](Self::drift_from)
+    /// for a one-off check.
+    ///
+    /// Enforcement stops when the returned handle is released or dropped.
+    /// Failures to re-apply are passed to the error hook.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use azizo_core::{AsusController, DisplayController};
+    /// use std::sync::Arc;
+    ///
+    /// let controller = Arc::new(AsusController::new()?);
+    /// controller.sync_all_sliders()?;
+    /// let lock = controller.lock_state(controller.get_state());
+    /// // ...
+    /// lock.release();
+    /// # Ok::<(), azizo_core::ControllerError>(())
+    /// ```
+    pub fn lock_state(self: &Arc<Self>, target: ControllerState) -> LockHandle {
+        LockHandle::spawn(Arc::clone(self), target)
+    }
 
-            type CallbackFn = unsafe extern "C" fn(i32, i32, *const i8);
-            type SetCallbackFn = unsafe extern "C" fn(CallbackFn, *mut c_void);
-            let set_callback: Symbol<SetCallbackFn> =
-                lib.get(b"SetCallbackForReturnOptimizationResult")?;
-            set_callback(callback_state::mode_callback, client);
+    /// Move the controller to a dedicated worker thread.
+    ///
+    /// Returns a cloneable [`CommandSink`] that other threads use to send it
+    /// commands. See [`CommandSink`] for details.
+    pub fn spawn_worker(self) -> CommandSink {
+        CommandSink::spawn(self)
+    }
 
-            Ok(Self { lib, client })
-        }
+    /// Apply a mode that reverts unless confirmed, like a monitor's "keep these
+    /// settings?" dialog.
+    ///
+    /// The previous mode and dimming are restored after `revert_after` unless
+    /// [`ConfirmationHandle::confirm`] is called first. Dropping the handle
+    /// without confirming reverts immediately, so a setting that leaves the
+    /// screen unreadable is always undone.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use azizo_core::{AsusController, EReadingMode};
+    /// use std::sync::Arc;
+    /// use std::time::Duration;
+    ///
+    /// let controller = Arc::new(AsusController::new()?);
+    /// let mode = EReadingMode::new(5, 50)?;
+    /// let pending = controller.apply_with_confirmation(&mode, Duration::from_secs(15))?;
+    /// // Ask the user whether to keep the change...
+    /// pending.confirm();
+    /// # Ok::<(), azizo_core::ControllerError>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the mode cannot be applied; nothing is reverted then.
+    pub fn apply_with_confirmation(
+        self: &Arc<Self>,
+        mode: &dyn DisplayMode,
+        revert_after: Duration,
+    ) -> Result<ConfirmationHandle, ControllerError> {
+        let previous = self.get_state();
+        self.set_mode(mode)?;
+        info!(
+            "applied {}, reverting in {:?} unless confirmed",
+            mode.describe(),
+            revert_after
+        );
+        Ok(ConfirmationHandle::spawn(
+            Arc::clone(self),
+            previous,
+            revert_after,
+        ))
     }
 
-    fn call_rpc_get(&self, symbol: &[u8]) -> Result<i64, ControllerError> {
-        unsafe {
-            type GetFn = unsafe extern "C" fn(*mut c_void) -> i64;
-            let func: Symbol<GetFn> = self.lib.get(symbol)?;
-            Ok(func(self.client))
+    /// Get a state snapshot with when each field was last updated.
+    ///
+    /// Lets a UI show e.g. "last updated 12s ago" and decide whether to call
+    /// [`sync_all_sliders`](DisplayController::sync_all_sliders) again.
+    pub fn get_state_timestamped(&self) -> Timestamped<ControllerState> {
+        Timestamped {
+            value: callback_state::snapshot(),
+            updated: callback_state::timestamps(),
         }
     }
 
-    /// Set a splendid mode with a value parameter.
+    /// Get the laptop model name, e.g. `"Zenbook UX3402ZA"`.
     ///
-    /// This is used internally by mode implementations.
-    pub fn set_splendid_mode(&self, symbol: &[u8], value: u8) -> Result<(), ControllerError> {
-        unsafe {
-            type SetModeFn = unsafe extern "C" fn(u8, *const i8, *mut c_void) -> i64;
-            let set_fn: Symbol<SetModeFn> = self.lib.get(symbol)?;
-            let empty_str = b"\0".as_ptr() as *const i8;
-            set_fn(value, empty_str, self.client);
-            Ok(())
-        }
+    /// Read from the SMBIOS product name, which is what WMI reports as
+    /// `Win32_ComputerSystem.Model`. Useful for model-specific calibration and bug
+    /// reports. Returns `None` if it cannot be read.
+    pub fn laptop_model(&self) -> Option<String> {
+        system::system_product_name()
     }
 
-    /// Set monochrome/e-reading mode with grayscale and temp.
+    /// Get the PID of the process holding the controller, if any.
     ///
-    /// This is used internally by [`EReadingMode`].
-    /// Temperature is -50 to +50 (0 is neutral).
-    pub fn set_monochrome_mode(&self, grayscale: u8, temp: i8) -> Result<(), ControllerError> {
-        unsafe {
-            type SetMonoFn = unsafe extern "C" fn(i32, *mut c_void) -> i64;
-            let set_mono: Symbol<SetMonoFn> = self.lib.get(b"MyOptSetSplendidMonochromeFunc")?;
-            let value = (grayscale as i32 * 256) + temp as i32 - 206;
-            set_mono(value, self.client);
-            Ok(())
-        }
+    /// Each controller records its process in shared memory for as long as it
+    /// exists. A tray app launched a second time can use this to find and
+    /// signal the running instance instead of creating a second controller.
+    /// Returns the current process's PID if this process holds it. If several
+    /// processes create controllers, the most recent one is reported.
+    pub fn current_owner_pid() -> Option<u32> {
+        system::owner_pid()
+    }
+
+    /// Get the cached e-reading parameters on the user-facing scale.
+    ///
+    /// Returns `(grayscale, temp)` with grayscale in 1-5 and temperature shifted
+    /// from -50..+50 to 0-100 (50 is neutral).
+    pub fn ereading_params(&self) -> (u8, u8) {
+        let state = self.get_state();
+        let grayscale = state.ereading_grayscale.clamp(1, 5);
+        let temp = EReadingMode::from_controller_state(&state).percent_temp();
+        (grayscale, temp)
     }
 
     /// Convert dimming from splendid units (40-100) to percentage (0-100).
+    ///
+    /// The 61 splendid values are spread over 101 percentages, so consecutive
+    /// splendid values are 1 or 2 percent apart. Percentages skipped this way
+    /// come back from [`percent_to_dimming`](Self::percent_to_dimming) one
+    /// off; see [`nearest_percent`](Self::nearest_percent).
     pub fn dimming_to_percent(splendid_value: i32) -> i32 {
         let clamped = splendid_value.clamp(40, 100);
         ((clamped - 40) as f32 / 60.0 * 100.0).round() as i32
     }
 
     /// Convert dimming from percentage (0-100) to splendid units (40-100).
+    ///
+    /// Rounds to the nearest splendid value, so neighbouring percentages can
+    /// share one. Converting back with [`dimming_to_percent`](Self::dimming_to_percent)
+    /// gives the original percentage to within 1.
     pub fn percent_to_dimming(percent: i32) -> i32 {
         40 + (percent as f32 / 100.0 * 60.0).round() as i32
     }
 
+    /// Convert a percentage (0-100) to splendid units only if it converts back
+    /// unchanged.
+    ///
+    /// Returns `None` for percentages that fall between two splendid values;
+    /// use [`nearest_percent`](Self::nearest_percent) to snap them.
+    pub fn percent_to_dimming_exact(percent: i32) -> Option<i32> {
+        let dimming = Self::percent_to_dimming(percent);
+        ((0..=100).contains(&percent) && Self::dimming_to_percent(dimming) == percent)
+            .then_some(dimming)
+    }
+
+    /// The percentage the hardware will actually report after setting `percent`.
+    ///
+    /// Clamps into 0-100 and snaps to the nearest percentage that round-trips
+    /// through splendid units, e.g. for a slider that shouldn't jump after the
+    /// device confirms the value.
+    pub fn nearest_percent(percent: i32) -> i32 {
+        Self::dimming_to_percent(Self::percent_to_dimming(percent.clamp(0, 100)))
+    }
+
+    /// Convert dimming from splendid units (40-100) to an unrounded percentage (0.0-100.0).
+    ///
+    /// For continuous sliders and fades; see [`dimming_to_percent`](Self::dimming_to_percent)
+    /// for the rounded value.
+    pub fn dimming_to_percent_f32(splendid_value: i32) -> f32 {
+        let clamped = splendid_value.clamp(40, 100);
+        (clamped - 40) as f32 / 60.0 * 100.0
+    }
+
+    /// Convert a percentage (0.0-100.0) to the nearest splendid value (40-100).
+    ///
+    /// Rounding happens only here, so intermediate values can stay continuous.
+    /// Out-of-range and NaN percentages are clamped.
+    pub fn percent_f32_to_dimming(percent: f32) -> i32 {
+        let percent = if percent.is_nan() {
+            0.0
+        } else {
+            percent.clamp(0.0, 100.0)
+        };
+        40 + (percent / 100.0 * 60.0).round() as i32
+    }
+
+    /// Convert a luminance in nits to the nearest splendid value (40-100), given the
+    /// panel's luminance at full brightness.
+    ///
+    /// See [`set_dimming_nits`](Self::set_dimming_nits) for the assumed mapping.
+    pub fn nits_to_dimming(nits: f32, max_luminance: f32) -> i32 {
+        if max_luminance <= 0.0 || nits.is_nan() {
+            return 100;
+        }
+        ((nits / max_luminance * 100.0).round() as i32).clamp(40, 100)
+    }
+
+    /// Find the percentage (0-100) that converts to the given splendid value (40-100).
+    ///
+    /// Percent↔splendid conversion is lossy, so this picks the percentage closest to
+    /// [`dimming_to_percent`](Self::dimming_to_percent) whose
+    /// [`percent_to_dimming`](Self::percent_to_dimming) lands exactly on `target`.
+    /// Out-of-range targets are clamped.
+    ///
+    /// The result is for display, e.g. a slider label. Don't pass it to
+    /// [`set_dimming_percent`](DisplayController::set_dimming_percent), which
+    /// rounds to 10% steps and usually misses `target`; set `target` itself
+    /// with [`set_dimming`](DisplayController::set_dimming).
+    pub fn percent_for_splendid(target: i32) -> i32 {
+        let target = target.clamp(40, 100);
+        let estimate = Self::dimming_to_percent(target);
+        (0..=100)
+            .min_by_key(|&percent| {
+                (
+                    (Self::percent_to_dimming(percent) - target).abs(),
+                    (percent - estimate).abs(),
+                )
+            })
+            .unwrap_or(estimate)
+    }
+
     fn mode_from_state(
         &self,
         state: &ControllerState,
     ) -> Result<Box<dyn DisplayMode>, ControllerError> {
-        match (state.mode_id, state.is_monochrome) {
-            (1, false) => Ok(Box::new(NormalMode::new())),
-            (2, false) => Ok(Box::new(VividMode::new())),
-            (6, false) => Ok(Box::new(ManualMode::from_controller_state(state))),
-            (7, false) => Ok(Box::new(EyeCareMode::from_controller_state(state))),
-            (_, true) => {
-                callback_state::store_last_non_ereading_mode(state.mode_id);
-                Ok(Box::new(EReadingMode::from_controller_state(state)))
-            }
-            _ => Err(ControllerError::ModeNotDetected),
+        if state.is_monochrome {
+            callback_state::store_last_non_ereading_mode(state.mode_id);
+        }
+        modes::mode_from_state(state)
+    }
+
+    /// Re-apply the mode and dimming captured in a state snapshot.
+    pub(crate) fn restore_state(&self, state: &ControllerState) -> Result<(), ControllerError> {
+        if state.is_monochrome {
+            callback_state::store_last_non_ereading_mode(state.last_non_ereading_mode);
+        }
+        let mode = modes::mode_from_state(state)?;
+        self.set_mode(&*mode)?;
+        if state.dimming >= 40 {
+            self.set_dimming(state.dimming)?;
         }
+        Ok(())
     }
 
     fn restore_last_mode(&self, state: &ControllerState) -> Box<dyn DisplayMode> {
-        match state.last_non_ereading_mode {
-            2 => Box::new(VividMode::new()),
-            6 => Box::new(ManualMode::from_controller_state(state)),
-            7 => Box::new(EyeCareMode::from_controller_state(state)),
-            _ => Box::new(NormalMode::new()),
+        let kind = ModeKind::from_mode_id(state.last_non_ereading_mode)
+            .unwrap_or(self.config.default_restore_mode);
+        match kind {
+            ModeKind::Vivid => Box::new(VividMode::new()),
+            ModeKind::Manual => Box::new(ManualMode::from_controller_state(state)),
+            ModeKind::EyeCare => Box::new(EyeCareMode::from_controller_state(state)),
+            ModeKind::Normal | ModeKind::EReading => Box::new(NormalMode::new()),
         }
     }
 }
@@ -318,11 +2252,31 @@ impl DisplayController for AsusController {
         callback_state::snapshot()
     }
 
+    fn state_blocking(&self, timeout: Duration) -> Result<ControllerState, ControllerError> {
+        self.request_and_wait_for(
+            &[
+                (b"MyOptGetSplendidColorModeFunc", callback_state::MODE),
+                (b"MyOptGetSplendidManualModeFunc", callback_state::MANUAL),
+                (b"MyOptGetSplendidEyecareModeFunc", callback_state::EYECARE),
+                (
+                    b"MyOptGetSplendidMonochromeFunc",
+                    callback_state::MONOCHROME,
+                ),
+            ],
+            timeout,
+        )?;
+        Ok(self.get_state())
+    }
+
     fn refresh_sliders(&self) -> Result<(), ControllerError> {
-        self.call_rpc_get(b"MyOptGetSplendidManualModeFunc")?;
-        self.call_rpc_get(b"MyOptGetSplendidEyecareModeFunc")?;
-        self.call_rpc_get(b"MyOptGetSplendidMonochromeFunc")?;
-        Ok(())
+        self.request_and_wait(&[
+            (b"MyOptGetSplendidManualModeFunc", callback_state::MANUAL),
+            (b"MyOptGetSplendidEyecareModeFunc", callback_state::EYECARE),
+            (
+                b"MyOptGetSplendidMonochromeFunc",
+                callback_state::MONOCHROME,
+            ),
+        ])
     }
 
     fn sync_all_sliders(&self) -> Result<(), ControllerError> {
@@ -330,7 +2284,6 @@ impl DisplayController for AsusController {
 
         let _ = self.get_current_mode();
         self.refresh_sliders()?;
-        std::thread::sleep(std::time::Duration::from_millis(500));
 
         let state = self.get_state();
         debug!(
@@ -347,13 +2300,11 @@ impl DisplayController for AsusController {
 
     fn set_dimming(&self, level: i32) -> Result<(), ControllerError> {
         let level = level.clamp(40, 100);
-        unsafe {
-            type SetDimmingFn = unsafe extern "C" fn(i32, *const i8, *mut c_void) -> i64;
-            let set_dimming: Symbol<SetDimmingFn> = self.lib.get(b"MyOptSetSplendidDimmingFunc")?;
-
-            let empty_str = b"\0".as_ptr() as *const i8;
-            let result = set_dimming(level, empty_str, self.client);
+        self.with_dll(|dll| unsafe {
+            let set_dimming = dll.resolve(|s| s.set_dimming, b"MyOptSetSplendidDimmingFunc")?;
+            let result = set_dimming(level, c"".as_ptr(), dll.client);
             debug!("set dimming to {}, result: {}", level, result);
+            session_log::record(format_args!("set dimming {} -> {}", level, result));
 
             if result == 0 {
                 callback_state::store_dimming(level);
@@ -361,36 +2312,80 @@ impl DisplayController for AsusController {
             } else {
                 Err(ControllerError::DimmingFailed(result))
             }
-        }
+        })?;
+        self.mirror_dimming(level);
+        Ok(())
     }
 
     fn set_dimming_percent(&self, percent: i32) -> Result<(), ControllerError> {
-        // Enforce 10% increments
-        let percent = (percent as f32 / 10.0).round() as i32 * 10;
-        let splendid_value = Self::percent_to_dimming(percent.clamp(0, 100));
-        self.set_dimming(splendid_value)
+        self.set_dimming(Self::percent_to_dimming(Self::dimming_step(percent)))
+    }
+
+    fn set_base_dimming_percent(&self, percent: i32) -> Result<(), ControllerError> {
+        let level = Self::percent_to_dimming(percent.clamp(0, 100));
+        let mut layers = self.dimming_layers.lock().unwrap();
+        match layers.set_base(level) {
+            Some(level) => self.set_dimming(level),
+            None => Ok(()),
+        }
+    }
+
+    fn push_dimming_override(&self, percent: i32) -> Result<(), ControllerError> {
+        let level = Self::percent_to_dimming(percent.clamp(0, 100));
+        let mut layers = self.dimming_layers.lock().unwrap();
+        let level = layers.push(level, self.get_state().dimming);
+        self.set_dimming(level)
+    }
+
+    fn pop_dimming_override(&self) -> Result<(), ControllerError> {
+        let mut layers = self.dimming_layers.lock().unwrap();
+        match layers.pop() {
+            Some(level) => self.set_dimming(level),
+            None => Ok(()),
+        }
+    }
+
+    fn base_dimming_percent(&self) -> Option<i32> {
+        let layers = self.dimming_layers.lock().unwrap();
+        layers.base().map(Self::dimming_to_percent)
     }
 
     fn get_current_mode(&self) -> Result<Box<dyn DisplayMode>, ControllerError> {
-        self.call_rpc_get(b"MyOptGetSplendidColorModeFunc")?;
-        std::thread::sleep(std::time::Duration::from_millis(500));
+        self.request_and_wait(&[(b"MyOptGetSplendidColorModeFunc", callback_state::MODE)])?;
 
         let state = self.get_state();
         self.mode_from_state(&state)
     }
 
     fn set_mode(&self, mode: &dyn DisplayMode) -> Result<(), ControllerError> {
-        mode.apply(self)
+        let state = self.get_state();
+        self.check_symbols(mode)?;
+        mode.apply(self)?;
+
+        // Only a change of mode counts, so dragging a slider keeps the previous mode
+        let changed = state.is_monochrome != mode.is_ereading()
+            || (!mode.is_ereading() && state.mode_id != mode.mode_id());
+        if changed && state.mode_id > 0 {
+            callback_state::store_previous_mode(ModeSnapshot::from_state(&state));
+        }
+        Ok(())
+    }
+
+    fn report_error(&self, error: &ControllerError) {
+        warn!("background operation failed: {}", error);
+        if let Some(hook) = self.error_hook.read().unwrap().as_ref() {
+            hook(error);
+        }
     }
 
     fn toggle_e_reading(&self) -> Result<Box<dyn DisplayMode>, ControllerError> {
         let current = self.get_current_mode()?;
-        debug!("current mode: {:?}", current);
+        debug!("current mode: {}", current.describe());
 
         let state = self.get_state();
         let target: Box<dyn DisplayMode> = if current.is_ereading() {
             let restored = self.restore_last_mode(&state);
-            info!("switching from e-reading to {:?}", restored);
+            info!("switching from e-reading to {}", restored.describe());
             restored
         } else {
             info!("switching to e-reading");
@@ -398,19 +2393,154 @@ impl DisplayController for AsusController {
         };
 
         self.set_mode(&*target)?;
+
+        // Re-assert dimming so the mode switch doesn't change perceived brightness
+        if state.dimming >= 40 {
+            self.set_dimming(state.dimming)?;
+        }
         Ok(target)
     }
 }
 
-impl Drop for AsusController {
-    fn drop(&mut self) {
-        unsafe {
-            type UninitFn = unsafe extern "C" fn(*mut c_void);
-            if let Ok(uninit) = self.lib.get::<UninitFn>(b"MyOptRpcClientUninitialize") {
-                uninit(self.client);
-            }
+// =============================================================================
+// Builder
+// =============================================================================
+
+/// Assumed panel luminance at full brightness when none is configured.
+const DEFAULT_MAX_LUMINANCE: f32 = 400.0;
+
+/// Settings captured by [`AsusControllerBuilder`].
+#[derive(Debug, Clone)]
+struct ControllerConfig {
+    package_retries: u32,
+    callback_timeout: Duration,
+    max_luminance: f32,
+    default_restore_mode: ModeKind,
+    mode_coalesce_window: Duration,
+    suppress_dll_output: bool,
+    package_family: Option<String>,
+}
+
+impl ControllerConfig {
+    /// Package families to search, the user-supplied one first.
+    fn package_families(&self) -> Vec<&str> {
+        self.package_family
+            .as_deref()
+            .into_iter()
+            .chain(PACKAGE_FAMILY_CANDIDATES.iter().copied())
+            .collect()
+    }
+}
+
+impl Default for ControllerConfig {
+    fn default() -> Self {
+        Self {
+            package_retries: 2,
+            callback_timeout: Duration::from_secs(2),
+            max_luminance: DEFAULT_MAX_LUMINANCE,
+            default_restore_mode: ModeKind::Normal,
+            mode_coalesce_window: Duration::from_millis(150),
+            suppress_dll_output: false,
+            package_family: None,
         }
-        INSTANCE_EXISTS.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Builder for [`AsusController`].
+///
+/// Obtain one with [`AsusController::builder`].
+#[derive(Debug, Clone, Default)]
+pub struct AsusControllerBuilder {
+    config: ControllerConfig,
+}
+
+impl AsusControllerBuilder {
+    /// Create a builder with default settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set how many times ASUS package discovery is retried (default 2).
+    ///
+    /// Right after boot the package service may not be ready yet, so discovery
+    /// can fail transiently. Each retry waits a short, jittered backoff first.
+    pub fn package_retries(mut self, retries: u32) -> Self {
+        self.config.package_retries = retries;
+        self
+    }
+
+    /// Search this package family before the built-in
+    /// [`PACKAGE_FAMILY_CANDIDATES`].
+    ///
+    /// For MyASUS or Armoury Crate builds published under a family name azizo
+    /// doesn't know yet.
+    pub fn package_family(mut self, name: impl Into<String>) -> Self {
+        self.config.package_family = Some(name.into());
+        self
+    }
+
+    /// Set how long queries wait for the DLL's reply callback (default 2s).
+    ///
+    /// Queries such as [`get_current_mode`](DisplayController::get_current_mode)
+    /// return as soon as the callback arrives, so this only matters when it
+    /// doesn't; they then fail with [`ControllerError::CallbackTimeout`].
+    pub fn callback_timeout(mut self, timeout: Duration) -> Self {
+        self.config.callback_timeout = timeout;
+        self
+    }
+
+    /// Hide anything the DLL prints to stdout (default off).
+    ///
+    /// While a query waits for the DLL's reply callbacks, the process's stdout
+    /// handle is pointed at `NUL`. This affects the whole
+    /// process, so output from other threads during those windows is hidden
+    /// too. azizo-core itself never prints; it logs through [`log`].
+    pub fn suppress_dll_output(mut self, suppress: bool) -> Self {
+        self.config.suppress_dll_output = suppress;
+        self
+    }
+
+    /// Set how long queries wait for the DLL's reply callback.
+    #[deprecated(note = "queries now wait for the callback; use `callback_timeout`")]
+    pub fn sync_settle_time(self, settle_time: Duration) -> Self {
+        self.callback_timeout(settle_time)
+    }
+
+    /// Set the panel's luminance in nits at full brightness (default 400).
+    ///
+    /// Used by [`AsusController::set_dimming_nits`]. The default is a typical
+    /// value for ASUS laptop panels; use the calibrated or spec-sheet figure of
+    /// your panel for a closer mapping (OLED panels often reach 500-600 nits).
+    pub fn max_luminance(mut self, nits: f32) -> Self {
+        self.config.max_luminance = nits;
+        self
+    }
+
+    /// Set the mode restored when leaving e-reading with no known previous mode
+    /// (default [`ModeKind::Normal`]).
+    ///
+    /// This applies when e-reading was already active before the controller saw
+    /// any other mode. Manual and Eye Care use the cached slider values.
+    /// [`ModeKind::EReading`] is not a valid fallback and is treated as Normal.
+    pub fn default_restore_mode(mut self, kind: ModeKind) -> Self {
+        self.config.default_restore_mode = kind;
+        self
+    }
+
+    /// Set how long [`AsusController::set_mode_coalesced`] waits for newer
+    /// requests before applying the latest one (default 150ms).
+    pub fn mode_coalesce_window(mut self, window: Duration) -> Self {
+        self.config.mode_coalesce_window = window;
+        self
+    }
+
+    /// Create the controller with these settings.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`AsusController::new`].
+    pub fn build(self) -> Result<AsusController, ControllerError> {
+        AsusController::with_config(self.config)
     }
 }
 
@@ -418,10 +2548,56 @@ impl Drop for AsusController {
 // Windows Package Helpers
 // =============================================================================
 
-fn find_asus_package() -> Result<String, ControllerError> {
-    let family_name: Vec<u16> = "B9ECED6F.ASUSPCAssistant_qmba6cd70vzyy\0"
-        .encode_utf16()
-        .collect();
+/// Run package discovery, retrying transient failures with jittered backoff.
+fn with_retries<T>(
+    retries: u32,
+    mut f: impl FnMut() -> Result<T, ControllerError>,
+) -> Result<T, ControllerError> {
+    let mut attempt = 0;
+    loop {
+        match f() {
+            Err(
+                e
+                @ (ControllerError::PackageNotFound { .. } | ControllerError::PackagePathError(_)),
+            ) if attempt < retries => {
+                attempt += 1;
+                let jitter = RandomState::new().hash_one(attempt) % 100;
+                let delay = Duration::from_millis(200 * attempt as u64 + jitter);
+                warn!(
+                    "package discovery failed ({}), retrying in {:?} ({}/{})",
+                    e, delay, attempt, retries
+                );
+                std::thread::sleep(delay);
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Full name of the installed package from the first family that resolves.
+fn find_asus_package(families: &[&str]) -> Result<String, ControllerError> {
+    let mut code = 0;
+    for family in families {
+        match find_package_in_family(family) {
+            Ok(full_name) => return Ok(full_name),
+            Err(result) => {
+                debug!(
+                    "package family {} not found (error code: {})",
+                    family, result
+                );
+                code = result;
+            }
+        }
+    }
+    Err(ControllerError::PackageNotFound {
+        code,
+        tried: families.iter().map(|f| f.to_string()).collect(),
+    })
+}
+
+/// Full name of the newest package in a family, or the Win32 error code.
+fn find_package_in_family(family: &str) -> Result<String, u32> {
+    let family_name: Vec<u16> = format!("{}\0", family).encode_utf16().collect();
 
     let mut count = 0u32;
     let mut buffer_length = 0u32;
@@ -439,7 +2615,7 @@ fn find_asus_package() -> Result<String, ControllerError> {
     };
 
     if result != ERROR_INSUFFICIENT_BUFFER {
-        return Err(ControllerError::PackageNotFound(result));
+        return Err(result);
     }
 
     let mut package_names: Vec<*mut u16> = vec![std::ptr::null_mut(); count as usize];
@@ -458,7 +2634,7 @@ fn find_asus_package() -> Result<String, ControllerError> {
     };
 
     if result != 0 {
-        return Err(ControllerError::PackageNotFound(result));
+        return Err(result);
     }
 
     let full_name = unsafe {
@@ -499,6 +2675,262 @@ fn get_package_path(full_name: &str) -> Result<String, ControllerError> {
         return Err(ControllerError::PackagePathError(result));
     }
 
-    let len = buffer.iter().take_while(|&&c| c != 0).count();
-    Ok(String::from_utf16_lossy(&buffer[..len]))
+    Ok(wide_buffer_to_string(&buffer, buffer_length))
+}
+
+/// Decode a UTF-16 buffer filled by a Win32 API that reported `length`
+/// characters including the null terminator.
+pub(crate) fn wide_buffer_to_string(buffer: &[u16], length: u32) -> String {
+    let len = (length as usize).saturating_sub(1).min(buffer.len());
+    String::from_utf16_lossy(&buffer[..len])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        AsusController, ControllerConfig, DisplayController, Dll, Symbols, callback_state,
+    };
+    use std::ffi::{CString, c_void};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::{Arc, Mutex, MutexGuard};
+    use std::time::Duration;
+
+    /// Serializes tests that feed `mode_callback`, since its state is process-global.
+    fn lock_callback_state() -> MutexGuard<'static, ()> {
+        static LOCK: Mutex<()> = Mutex::new(());
+        LOCK.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    #[test]
+    fn test_callback_wait() {
+        let _lock = lock_callback_state();
+        let since = callback_state::received(callback_state::EYECARE);
+        assert!(!callback_state::wait_for(
+            callback_state::EYECARE,
+            since,
+            Duration::from_millis(20)
+        ));
+
+        let sender = std::thread::spawn(|| {
+            std::thread::sleep(Duration::from_millis(20));
+            callback_state::mode_callback(callback_state::EYECARE, 3, std::ptr::null());
+        });
+        assert!(callback_state::wait_for(
+            callback_state::EYECARE,
+            since,
+            Duration::from_secs(5)
+        ));
+        sender.join().unwrap();
+    }
+
+    #[test]
+    fn test_subscribe_receives_snapshots() {
+        let _lock = lock_callback_state();
+        let (sender, receiver) = std::sync::mpsc::channel();
+        callback_state::subscribe(sender);
+        callback_state::mode_callback(callback_state::MANUAL, 30, std::ptr::null());
+        assert!(receiver.recv_timeout(Duration::from_secs(5)).is_ok());
+
+        drop(receiver);
+        // The closed subscriber is dropped on the next callback
+        callback_state::mode_callback(callback_state::MANUAL, 30, std::ptr::null());
+    }
+
+    #[test]
+    fn test_session_history_is_bounded() {
+        let _lock = lock_callback_state();
+        callback_state::enable_history(3);
+        for level in [0, 1, 2, 3, 4, 0] {
+            callback_state::mode_callback(callback_state::EYECARE, level, std::ptr::null());
+        }
+        let history = callback_state::history();
+        callback_state::disable_history();
+
+        assert_eq!(history.len(), 3);
+        assert!(history.windows(2).all(|w| w[0].0 <= w[1].0));
+        assert!(callback_state::history().is_empty());
+    }
+
+    #[test]
+    fn test_symbols_contains() {
+        let symbols = Symbols {
+            getters: Vec::new(),
+            setters: Vec::new(),
+            set_monochrome: None,
+            set_dimming: None,
+        };
+        // Known but missing exports are answered from the cache
+        assert_eq!(
+            symbols.contains(b"MyOptGetSplendidColorModeFunc"),
+            Some(false)
+        );
+        assert_eq!(symbols.contains(b"MyOptSetSplendidFunc"), Some(false));
+        assert_eq!(
+            symbols.contains(b"MyOptSetSplendidDimmingFunc"),
+            Some(false)
+        );
+        assert_eq!(symbols.contains(b"MyOptSomethingNew"), None);
+    }
+
+    /// Stress the DLL callback from several threads while reading snapshots,
+    /// checking that no field is ever observed with a value that was not written.
+    #[test]
+    fn test_callback_concurrent_snapshots() {
+        let _lock = lock_callback_state();
+        callback_state::reset();
+        let payloads: Arc<Vec<CString>> = Arc::new(
+            ["0_1_0_1_1,50,0", "0_1_0_1_1,90,1"]
+                .iter()
+                .map(|s| CString::new(*s).unwrap())
+                .collect(),
+        );
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let writers: Vec<_> = (0..4)
+            .map(|i| {
+                let payloads = Arc::clone(&payloads);
+                std::thread::spawn(move || {
+                    for n in 0..2000 {
+                        let pick = (n + i) % 2;
+                        let mode = [1, 2][pick];
+                        callback_state::mode_callback(
+                            callback_state::MODE,
+                            mode,
+                            payloads[pick].as_ptr(),
+                        );
+                        callback_state::mode_callback(
+                            callback_state::MANUAL,
+                            [10, 90][pick],
+                            std::ptr::null(),
+                        );
+                        // Grayscale 2 / temp 10 and grayscale 5 / temp 40
+                        let ereading = [2 * 256 + 10 - 206, 5 * 256 + 40 - 206][pick];
+                        callback_state::mode_callback(
+                            callback_state::MONOCHROME,
+                            ereading,
+                            std::ptr::null(),
+                        );
+                    }
+                })
+            })
+            .collect();
+
+        let readers: Vec<_> = (0..2)
+            .map(|_| {
+                let stop = Arc::clone(&stop);
+                std::thread::spawn(move || {
+                    while !stop.load(Ordering::SeqCst) {
+                        let state = callback_state::snapshot();
+                        // Fields written by one callback are always seen together
+                        let mode_fields = (
+                            state.mode_id,
+                            state.dimming,
+                            state.is_monochrome,
+                            state.raw_callback.as_deref(),
+                        );
+                        assert!(
+                            matches!(
+                                mode_fields,
+                                (-1, -1, false, None)
+                                    | (1, 50, false, Some("0_1_0_1_1,50,0"))
+                                    | (2, 90, true, Some("0_1_0_1_1,90,1"))
+                            ),
+                            "mixed mode callback fields: {:?}",
+                            mode_fields
+                        );
+                        assert!([50, 10, 90].contains(&state.manual_slider));
+                        let ereading = (state.ereading_grayscale, state.ereading_temp);
+                        assert!(
+                            [(4, 0), (2, 10), (5, 40)].contains(&ereading),
+                            "mixed e-reading fields: {:?}",
+                            ereading
+                        );
+                    }
+                })
+            })
+            .collect();
+
+        for writer in writers {
+            writer.join().unwrap();
+        }
+        stop.store(true, Ordering::SeqCst);
+        for reader in readers {
+            reader.join().unwrap();
+        }
+        callback_state::reset();
+    }
+
+    /// A controller over the test binary itself, whose DLL calls all succeed
+    /// and whose external monitor brightness goes to `external_brightness`.
+    fn fake_controller(external_brightness: fn(u32) -> usize) -> AsusController {
+        unsafe extern "C" fn accept_dimming(_: i32, _: *const i8, _: *mut c_void) -> i64 {
+            0
+        }
+
+        #[cfg(unix)]
+        let lib = libloading::os::unix::Library::this().into();
+        #[cfg(windows)]
+        let lib = libloading::os::windows::Library::this().unwrap().into();
+        let dll = Dll {
+            lib,
+            symbols: Symbols {
+                getters: Vec::new(),
+                setters: Vec::new(),
+                set_monochrome: None,
+                set_dimming: Some(accept_dimming),
+            },
+            client: std::ptr::null_mut(),
+            _temp_copy: None,
+        };
+        AsusController::from_parts(ControllerConfig::default(), dll, None, external_brightness)
+    }
+
+    #[test]
+    fn test_every_dimming_setter_is_mirrored() {
+        static MIRRORED: Mutex<Vec<u32>> = Mutex::new(Vec::new());
+        fn record(percent: u32) -> usize {
+            MIRRORED.lock().unwrap().push(percent);
+            1
+        }
+        let take = || std::mem::take(&mut *MIRRORED.lock().unwrap());
+
+        let _lock = lock_callback_state();
+        callback_state::reset();
+        let controller = fake_controller(record);
+        controller.set_mirror_external(true);
+
+        controller.set_dimming(70).unwrap();
+        assert_eq!(take(), [50]);
+        controller.set_dimming_splendid_checked(100).unwrap();
+        assert_eq!(take(), [100]);
+        controller.set_dimming_percent(34).unwrap();
+        assert_eq!(take(), [30]);
+        controller.set_base_dimming_percent(80).unwrap();
+        assert_eq!(take(), [80]);
+        controller.push_dimming_override(20).unwrap();
+        assert_eq!(take(), [20]);
+        controller.pop_dimming_override().unwrap();
+        assert_eq!(take(), [80]);
+        controller.adjust_dimming_percent(-10).unwrap();
+        assert_eq!(take(), [70]);
+        controller.toggle_dimming(100, 40).unwrap();
+        assert_eq!(take(), [40]);
+        // Presets are rounded to the applied 10% steps
+        assert_eq!(controller.toggle_dimming(96, 44).unwrap(), 100);
+        assert_eq!(take(), [100]);
+        controller
+            .fade_dimming_percent_steps(60, Duration::ZERO, 3)
+            .unwrap();
+        assert_eq!(take().last(), Some(&60));
+        controller.fade_dimming_percent(0, Duration::ZERO).unwrap();
+        assert_eq!(take(), [0]);
+        controller.set_dimming_nits(f32::MAX).unwrap();
+        assert_eq!(take(), [100]);
+
+        controller.set_mirror_external(false);
+        controller.set_dimming(70).unwrap();
+        assert!(take().is_empty());
+        drop(controller);
+        callback_state::reset();
+    }
 }
@@ -0,0 +1,73 @@
+//! Brightness control of external monitors over DDC/CI.
+
+use log::debug;
+use windows_sys::Win32::Devices::Display::{
+    DestroyPhysicalMonitors, GetMonitorBrightness, GetNumberOfPhysicalMonitorsFromHMONITOR,
+    GetPhysicalMonitorsFromHMONITOR, PHYSICAL_MONITOR, SetMonitorBrightness,
+};
+use windows_sys::Win32::Foundation::{LPARAM, RECT};
+use windows_sys::Win32::Graphics::Gdi::{EnumDisplayMonitors, HDC, HMONITOR};
+use windows_sys::core::BOOL;
+
+unsafe extern "system" fn collect_monitor(
+    monitor: HMONITOR,
+    _hdc: HDC,
+    _rect: *mut RECT,
+    data: LPARAM,
+) -> BOOL {
+    let monitors = unsafe { &mut *(data as *mut Vec<HMONITOR>) };
+    monitors.push(monitor);
+    1
+}
+
+/// Set the brightness of every DDC/CI-capable monitor to `percent` (0-100) of
+/// its supported range, returning how many monitors accepted it.
+///
+/// Panels without DDC/CI (such as the built-in laptop screen) reject the
+/// request and are skipped.
+pub(crate) fn set_external_brightness(percent: u32) -> usize {
+    let percent = percent.min(100);
+    let mut monitors: Vec<HMONITOR> = Vec::new();
+    let mut updated = 0;
+
+    unsafe {
+        EnumDisplayMonitors(
+            std::ptr::null_mut(),
+            std::ptr::null(),
+            Some(collect_monitor),
+            &mut monitors as *mut Vec<HMONITOR> as LPARAM,
+        );
+
+        for monitor in monitors {
+            let mut count = 0u32;
+            if GetNumberOfPhysicalMonitorsFromHMONITOR(monitor, &mut count) == 0 || count == 0 {
+                continue;
+            }
+            let mut physical = vec![PHYSICAL_MONITOR::default(); count as usize];
+            if GetPhysicalMonitorsFromHMONITOR(monitor, count, physical.as_mut_ptr()) == 0 {
+                continue;
+            }
+
+            for entry in &physical {
+                let (mut min, mut current, mut max) = (0u32, 0u32, 0u32);
+                if GetMonitorBrightness(entry.hPhysicalMonitor, &mut min, &mut current, &mut max)
+                    == 0
+                {
+                    continue;
+                }
+                let value = min + (max.saturating_sub(min)) * percent / 100;
+                if SetMonitorBrightness(entry.hPhysicalMonitor, value) != 0 {
+                    debug!(
+                        "set external monitor brightness to {} ({}%)",
+                        value, percent
+                    );
+                    updated += 1;
+                }
+            }
+
+            DestroyPhysicalMonitors(count, physical.as_ptr());
+        }
+    }
+
+    updated
+}
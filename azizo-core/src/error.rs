@@ -3,9 +3,17 @@
 /// Errors that can occur when using the ASUS display controller.
 #[derive(Debug, thiserror::Error)]
 pub enum ControllerError {
-    /// The ASUS PC Assistant package was not found.
-    #[error("Package not found (error code: {0})")]
-    PackageNotFound(u32),
+    /// None of the candidate ASUS package families is installed.
+    #[error(
+        "Package not found, tried {} (last error code: {code})",
+        tried.join(", ")
+    )]
+    PackageNotFound {
+        /// Win32 error code from the last family that was tried.
+        code: u32,
+        /// Package family names that were searched, in order.
+        tried: Vec<String>,
+    },
 
     /// Failed to get the package installation path.
     #[error("Failed to get package path (error code: {0})")]
@@ -19,6 +27,10 @@ pub enum ControllerError {
     #[error("RPC initialization failed")]
     RpcInitFailed,
 
+    /// The DLL is not loaded because a previous reload failed.
+    #[error("DLL not loaded - reload the library to recover")]
+    LibraryNotLoaded,
+
     /// Attempted to create a second controller instance.
     #[error("Controller already initialized - only one instance allowed")]
     AlreadyInitialized,
@@ -36,14 +48,74 @@ pub enum ControllerError {
         max: u8,
     },
 
+    /// A dimming level was outside the valid range.
+    #[error("Invalid dimming value {value} (expected {min}-{max})")]
+    InvalidDimming {
+        /// The invalid value provided.
+        value: i32,
+        /// Minimum allowed value.
+        min: i32,
+        /// Maximum allowed value.
+        max: i32,
+    },
+
+    /// An e-reading temperature was outside the valid range.
+    #[error("Invalid e-reading temperature {value} (expected {min}-{max})")]
+    InvalidTemperature {
+        /// The invalid value provided.
+        value: i32,
+        /// Minimum allowed value.
+        min: i32,
+        /// Maximum allowed value.
+        max: i32,
+    },
+
+    /// The local copy of the DLL is a different size than the original.
+    #[error("DLL copy was incomplete ({actual} of {expected} bytes)")]
+    IncompleteDllCopy {
+        /// Size of the DLL in the ASUS package.
+        expected: u64,
+        /// Size of the local copy.
+        actual: u64,
+    },
+
+    /// Text such as a saved profile could not be parsed.
+    #[error("Parse error: {0}")]
+    Parse(String),
+
     /// An I/O error occurred (e.g., copying the DLL).
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 
+    /// The loaded DLL does not export a required symbol.
+    #[error("DLL symbol not found: {0}")]
+    SymbolNotFound(String),
+
     /// Failed to detect the current display mode.
     #[error("Failed to get current mode")]
     ModeNotDetected,
 
+    /// The controller state did not reflect an applied change before the timeout.
+    #[error("Change not confirmed by the controller within {0:?}")]
+    VerifyTimeout(std::time::Duration),
+
+    /// The DLL did not answer a query within the callback timeout.
+    #[error("No callback from the controller within {0:?}")]
+    CallbackTimeout(std::time::Duration),
+
+    /// A mode ID this crate cannot apply.
+    #[error("Unknown mode ID {0}")]
+    UnknownMode(i32),
+
+    /// The panel reported a different dimming level than was set.
+    #[error("Dimming {requested} was set but the panel reports {reported}")]
+    DimmingMismatch {
+        /// The requested level in splendid units.
+        requested: i32,
+        /// The level reported back by the DLL.
+        reported: i32,
+    },
+
     /// Failed to set the dimming level.
     #[error("Failed to set dimming (error code: {0})")]
     DimmingFailed(i64),
@@ -22,8 +22,7 @@
 //!     controller.sync_all_sliders()?;
 //!
 //!     // Get current state
-//!     let state = controller.get_state();
-//!     println!("Current dimming: {}%", AsusController::dimming_to_percent(state.dimming));
+//!     println!("Current dimming: {}%", controller.dimming_percent());
 //!
 //!     // Set a mode
 //!     controller.set_mode(&NormalMode::new())?;
@@ -60,18 +59,53 @@
 
 #![warn(missing_docs)]
 
+#[cfg(feature = "async")]
+mod async_controller;
+mod automation;
+mod callback;
+mod confirm;
 mod controller;
+mod ddc;
 mod error;
+mod lock;
 mod mock;
 mod modes;
+mod power;
+mod profile;
+mod ramp;
+mod screen_time;
+mod session_log;
 mod state;
+mod system;
+mod theme;
+mod worker;
 
 // Re-export public API
-pub use controller::{AsusController, DisplayController};
+#[cfg(feature = "async")]
+pub use async_controller::AsyncController;
+pub use automation::{AutomationGuard, TaskInfo, TaskKind, TaskRegistry, WatcherOptions};
+pub use callback::ModeCallbackPayload;
+pub use confirm::ConfirmationHandle;
+pub use controller::{
+    AsusController, AsusControllerBuilder, AsusControllerHandle, DisplayController, EReadingHook,
+    ErrorHook, PACKAGE_FAMILY_CANDIDATES, ValueMapper,
+};
 pub use error::ControllerError;
-pub use mock::MockController;
-pub use modes::{DisplayMode, EReadingMode, EyeCareMode, ManualMode, NormalMode, VividMode};
-pub use state::ControllerState;
+pub use lock::LockHandle;
+pub use mock::{MockCall, MockController};
+pub use modes::{
+    DisplayMode, EReadingMode, EReadingPreset, EyeCareMode, ManualMode, ModeKind, ModeSnapshot,
+    NormalMode, VividMode, mode_from_state, mode_name,
+};
+pub use power::PowerWatcher;
+pub use profile::{ImportMode, Profile, ProfileStore};
+pub use ramp::{DayRamp, EyeCareRamp, MANUAL_MAX_KELVIN, MANUAL_MIN_KELVIN};
+pub use screen_time::ScreenTimeTracker;
+pub use state::{
+    ControllerState, FullStatus, SliderPercents, StateDiff, StateTimestamps, Timestamped,
+};
+pub use theme::{SystemThemeWatcher, apps_use_light_theme};
+pub use worker::{CommandSink, Reply};
 
 #[cfg(test)]
 mod tests {
@@ -93,6 +127,35 @@ mod tests {
         assert_eq!(mode.mode_id(), 1);
     }
 
+    #[test]
+    fn test_mock_stores_ereading_temp() {
+        let mock = MockController::new();
+        mock.set_mode(&EReadingMode::new(2, -30).unwrap()).unwrap();
+
+        let state = mock.get_state();
+        assert_eq!((state.ereading_grayscale, state.ereading_temp), (2, -30));
+        let mode = mock.get_current_mode().unwrap();
+        assert_eq!(
+            mode.snapshot(),
+            ModeSnapshot::EReading {
+                grayscale: 2,
+                temp: -30
+            }
+        );
+    }
+
+    #[test]
+    fn test_toggle_ereading_preserves_dimming() {
+        let mock = MockController::new();
+        mock.set_dimming(55).unwrap();
+
+        mock.toggle_e_reading().unwrap();
+        assert_eq!(mock.get_state().dimming, 55);
+
+        mock.toggle_e_reading().unwrap();
+        assert_eq!(mock.get_state().dimming, 55);
+    }
+
     #[test]
     fn test_mock_controller_dimming() {
         let mock = MockController::new();
@@ -105,6 +168,139 @@ mod tests {
         assert_eq!(mock.get_state().dimming, expected);
     }
 
+    #[test]
+    fn test_mock_state_blocking() {
+        let mock = MockController::new();
+        mock.set_dimming(70).unwrap();
+
+        let state = mock
+            .state_blocking(std::time::Duration::from_millis(1))
+            .unwrap();
+        assert_eq!(state, mock.get_state());
+        assert_eq!(state.dimming, 70);
+    }
+
+    #[test]
+    fn test_mock_fade_dimming_percent() {
+        let mock = MockController::new();
+        mock.fade_dimming_percent(30, std::time::Duration::from_secs(10))
+            .unwrap();
+        assert_eq!(
+            mock.get_state().dimming,
+            AsusController::percent_to_dimming(30)
+        );
+        mock.fade_dimming_percent(150, std::time::Duration::ZERO)
+            .unwrap();
+        assert_eq!(mock.get_state().dimming, 100);
+    }
+
+    #[test]
+    fn test_mock_dimming_getters() {
+        let mock = MockController::new();
+        mock.set_dimming(70).unwrap();
+        assert_eq!(mock.dimming_raw(), 70);
+        assert_eq!(mock.dimming_percent(), 50);
+    }
+
+    #[test]
+    fn test_mock_cycle_mode() {
+        let mock = MockController::new();
+        mock.set_mode(&EyeCareMode::new(3).unwrap()).unwrap();
+        let order: Vec<_> = (0..5)
+            .map(|_| mock.cycle_mode().unwrap().snapshot())
+            .collect();
+        assert_eq!(
+            order,
+            [
+                ModeSnapshot::Normal,
+                ModeSnapshot::Vivid,
+                ModeSnapshot::Manual(50),
+                ModeSnapshot::EyeCare(3),
+                ModeSnapshot::Normal,
+            ]
+        );
+
+        mock.toggle_e_reading().unwrap();
+        assert_eq!(mock.cycle_mode().unwrap().mode_id(), 2);
+        let state = mock.get_state();
+        assert!(state.is_monochrome);
+        assert_eq!(state.mode_id, 2);
+    }
+
+    #[test]
+    fn test_mock_records_calls() {
+        let mock = MockController::new();
+        mock.set_mode(&EyeCareMode::new(3).unwrap()).unwrap();
+        mock.toggle_e_reading().unwrap();
+        mock.adjust_dimming_percent(10).unwrap();
+        mock.get_state();
+        assert_eq!(
+            mock.calls(),
+            [
+                MockCall::SetMode(7),
+                MockCall::ToggleEReading,
+                MockCall::SetBaseDimmingPercent(60),
+            ]
+        );
+
+        mock.clear_calls();
+        assert!(mock.calls().is_empty());
+    }
+
+    #[test]
+    fn test_mock_queued_failures() {
+        let mock = MockController::new();
+        let before = mock.get_state();
+
+        mock.fail_next(ControllerError::DimmingFailed(5));
+        assert!(matches!(
+            mock.set_dimming_percent(20),
+            Err(ControllerError::DimmingFailed(5))
+        ));
+        assert_eq!(mock.get_state(), before);
+        mock.set_dimming_percent(20).unwrap();
+
+        mock.fail_mode(ControllerError::RpcInitFailed);
+        mock.fail_dimming(ControllerError::DimmingFailed(7));
+        mock.sync_all_sliders().unwrap();
+        assert!(mock.set_mode(&VividMode::new()).is_err());
+        assert!(mock.toggle_e_reading().is_ok());
+        assert!(matches!(
+            mock.set_dimming(80),
+            Err(ControllerError::DimmingFailed(7))
+        ));
+        mock.set_dimming(80).unwrap();
+    }
+
+    #[test]
+    fn test_adjust_dimming_percent() {
+        let mock = MockController::new();
+        mock.set_dimming_percent(50).unwrap();
+
+        assert_eq!(mock.adjust_dimming_percent(10).unwrap(), 60);
+        assert_eq!(
+            mock.get_state().dimming,
+            AsusController::percent_to_dimming(60)
+        );
+        assert_eq!(mock.adjust_dimming_percent(-100).unwrap(), 0);
+        assert_eq!(mock.adjust_dimming_percent(200).unwrap(), 100);
+    }
+
+    #[test]
+    fn test_set_dimming_splendid_checked() {
+        let mock = MockController::new();
+
+        mock.set_dimming_splendid_checked(55).unwrap();
+        assert_eq!(mock.get_state().dimming, 55);
+
+        assert!(matches!(
+            mock.set_dimming_splendid_checked(39),
+            Err(ControllerError::InvalidDimming { value: 39, .. })
+        ));
+        assert!(mock.set_dimming_splendid_checked(101).is_err());
+        assert_eq!(mock.get_state().dimming, 55);
+    }
+
     #[test]
     fn test_dimming_conversion() {
         assert_eq!(AsusController::percent_to_dimming(0), 40);
@@ -116,6 +312,868 @@ mod tests {
         assert_eq!(AsusController::dimming_to_percent(70), 50);
     }
 
+    #[test]
+    fn test_percent_round_trip_within_one() {
+        let mut exact = 0;
+        for percent in 0..=100 {
+            let nearest = AsusController::nearest_percent(percent);
+            assert!(
+                (nearest - percent).abs() <= 1,
+                "{} came back as {}",
+                percent,
+                nearest
+            );
+            assert_eq!(AsusController::nearest_percent(nearest), nearest);
+            match AsusController::percent_to_dimming_exact(percent) {
+                Some(dimming) => {
+                    assert_eq!(nearest, percent);
+                    assert_eq!(dimming, AsusController::percent_to_dimming(percent));
+                    exact += 1;
+                }
+                None => assert_ne!(nearest, percent),
+            }
+        }
+        // One exact percentage per splendid value
+        assert_eq!(exact, 61);
+        assert_eq!(AsusController::nearest_percent(150), 100);
+        assert_eq!(AsusController::percent_to_dimming_exact(-1), None);
+    }
+
+    #[test]
+    fn test_dimming_round_trip_does_not_drift() {
+        for splendid in 40..=100 {
+            let mut value = splendid;
+            for _ in 0..10 {
+                value =
+                    AsusController::percent_to_dimming(AsusController::dimming_to_percent(value));
+                assert!(
+                    (value - splendid).abs() <= 1,
+                    "{} drifted to {}",
+                    splendid,
+                    value
+                );
+            }
+            assert_eq!(value, splendid);
+        }
+    }
+
+    #[test]
+    fn test_percent_for_splendid() {
+        for target in 40..=100 {
+            let percent = AsusController::percent_for_splendid(target);
+            assert_eq!(AsusController::percent_to_dimming(percent), target);
+        }
+
+        assert_eq!(AsusController::percent_for_splendid(0), 0);
+        assert_eq!(AsusController::percent_for_splendid(200), 100);
+    }
+
+    #[test]
+    fn test_describe() {
+        assert_eq!(NormalMode::new().describe(), "Normal");
+        assert_eq!(ManualMode::new(50).unwrap().describe(), "Manual (50)");
+        assert_eq!(EyeCareMode::new(3).unwrap().describe(), "Eye Care (3)");
+        assert_eq!(
+            EReadingMode::new(4, -10).unwrap().describe(),
+            "E-Reading (grayscale 4, temp -10)"
+        );
+    }
+
+    #[test]
+    fn test_mode_names() {
+        assert_eq!(NormalMode::new().name(), "Normal");
+        assert_eq!(VividMode::new().name(), "Vivid");
+        assert_eq!(ManualMode::new(50).unwrap().name(), "Manual");
+        assert_eq!(EyeCareMode::new(3).unwrap().name(), "Eye Care");
+        assert_eq!(EReadingMode::new(4, 0).unwrap().name(), "E-Reading");
+    }
+
+    #[test]
+    fn test_state_timestamps_max_age() {
+        use std::time::Instant;
+
+        let mut updated = StateTimestamps::new();
+        assert_eq!(updated.max_age(), None);
+
+        let now = Instant::now();
+        updated.mode = Some(now);
+        updated.dimming = Some(now);
+        updated.manual_slider = Some(now);
+        updated.eyecare_level = Some(now);
+        assert_eq!(updated.max_age(), None);
+
+        updated.ereading = Some(now);
+        assert!(updated.max_age().is_some());
+    }
+
+    #[test]
+    fn test_day_ramp() {
+        use std::sync::Arc;
+        use std::time::Duration;
+
+        let hour = |h: f32| Duration::from_secs_f32(h * 3600.0);
+        let mock = Arc::new(MockController::new());
+        let ramp = DayRamp::new(mock.clone(), 6500, 3400);
+
+        assert_eq!(ramp.kelvin_at(hour(12.0)), 6500);
+        assert_eq!(ramp.kelvin_at(hour(0.0)), 3400);
+        assert_eq!(ramp.kelvin_at(hour(7.0)), 4950);
+        assert_eq!(ramp.kelvin_at(hour(19.0)), 4950);
+        assert_eq!(ramp.manual_value_at(hour(12.0)), 50);
+
+        ramp.tick(hour(12.0)).unwrap();
+        let state = mock.get_state();
+        assert_eq!(state.mode_id, 6);
+        assert_eq!(state.manual_slider, 50);
+
+        // Day across midnight, with the sunset transition starting before it
+        let ramp = DayRamp::new(mock.clone(), 6500, 3400)
+            .sunrise(hour(20.0))
+            .sunset(hour(0.5))
+            .transition(hour(2.0));
+        assert_eq!(ramp.kelvin_at(hour(12.0)), 3400);
+        assert_eq!(ramp.kelvin_at(hour(22.0)), 6500);
+        assert_eq!(ramp.kelvin_at(hour(20.0)), 4950);
+        assert_eq!(ramp.kelvin_at(hour(0.5)), 4950);
+        assert_eq!(ramp.kelvin_at(hour(23.5)), 6500);
+        assert!((3400..4950).contains(&ramp.kelvin_at(hour(1.0))));
+    }
+
+    #[test]
+    fn test_screen_time_tracker() {
+        use std::time::Duration;
+
+        let step = ScreenTimeTracker::RAMP_STEP;
+        assert_eq!(ScreenTimeTracker::level_for(Duration::ZERO), 0);
+        assert_eq!(
+            ScreenTimeTracker::level_for(step - Duration::from_secs(1)),
+            0
+        );
+        assert_eq!(ScreenTimeTracker::level_for(step), 1);
+        assert_eq!(ScreenTimeTracker::level_for(step * 3), 3);
+        assert_eq!(ScreenTimeTracker::level_for(step * 10), 4);
+
+        let tracker = ScreenTimeTracker::new(Duration::from_secs(300));
+        assert_eq!(tracker.active_time(), Duration::ZERO);
+        tracker.record_idle(Duration::from_secs(60));
+        assert!(tracker.active_time() >= Duration::from_secs(60));
+        // Further input keeps the original start
+        tracker.record_idle(Duration::ZERO);
+        assert!(tracker.active_time() >= Duration::from_secs(60));
+        tracker.record_idle(Duration::from_secs(300));
+        assert_eq!(tracker.active_time(), Duration::ZERO);
+        assert_eq!(tracker.recommended_eyecare_level(), 0);
+    }
+
+    #[test]
+    fn test_eyecare_ramp() {
+        use std::sync::Arc;
+        use std::time::Duration;
+
+        let hour = |h: f32| Duration::from_secs_f32(h * 3600.0);
+        let mock = Arc::new(MockController::new());
+        let ramp = EyeCareRamp::new(mock.clone(), 0, 4).transition(hour(4.0));
+
+        assert_eq!(ramp.level_at(hour(12.0)), 0);
+        assert_eq!(ramp.level_at(hour(23.0)), 4);
+        assert_eq!(ramp.level_at(hour(19.0)), 2);
+        // Steps through every level during the evening transition
+        let levels: Vec<u8> = (0..=16)
+            .map(|q| ramp.level_at(hour(17.0 + q as f32 / 4.0)))
+            .collect();
+        assert!(levels.windows(2).all(|w| w[1] == w[0] || w[1] == w[0] + 1));
+        assert_eq!(levels.first(), Some(&0));
+        assert_eq!(levels.last(), Some(&4));
+
+        ramp.tick(hour(23.0)).unwrap();
+        let state = mock.get_state();
+        assert_eq!(state.mode_id, 7);
+        assert_eq!(state.eyecare_level, 4);
+    }
+
+    #[test]
+    fn test_command_sink() {
+        let sink = CommandSink::spawn(MockController::new());
+        let other = sink.clone();
+
+        sink.set_mode(ModeSnapshot::EyeCare(3))
+            .recv()
+            .unwrap()
+            .unwrap();
+        other.set_dimming(55).recv().unwrap().unwrap();
+        let state = sink.get_state().recv().unwrap().unwrap();
+        assert_eq!(state.mode_id, 7);
+        assert_eq!(state.dimming, 55);
+
+        let mode = other.toggle_e_reading().recv().unwrap().unwrap();
+        assert!(matches!(mode, ModeSnapshot::EReading { .. }));
+        assert!(
+            sink.set_mode(ModeSnapshot::Unknown(9))
+                .recv()
+                .unwrap()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_ereading_presets() {
+        for preset in EReadingPreset::ALL {
+            let mode = preset.to_mode();
+            assert!(EReadingMode::new(mode.grayscale, mode.temp).is_ok());
+        }
+
+        let mock = MockController::new();
+        mock.set_mode(&EReadingPreset::Sepia.to_mode()).unwrap();
+        let state = mock.get_state();
+        assert!(state.is_monochrome);
+        assert_eq!(state.ereading_grayscale, 4);
+    }
+
+    #[test]
+    fn test_ereading_temp_scales() {
+        for percent in 0..=100u8 {
+            let mode = EReadingMode::with_percent_temp(3, percent).unwrap();
+            assert_eq!(mode.temp as i32, percent as i32 - 50);
+            assert_eq!(mode.percent_temp(), percent);
+
+            let signed = EReadingMode::with_signed_temp(3, mode.temp).unwrap();
+            assert_eq!(signed.percent_temp(), percent);
+            assert_eq!(
+                AsusController::encode_monochrome(3, signed.temp),
+                AsusController::encode_monochrome(3, mode.temp)
+            );
+        }
+
+        assert_eq!(EReadingMode::with_percent_temp(3, 50).unwrap().temp, 0);
+        assert!(EReadingMode::with_percent_temp(3, 101).is_err());
+        assert!(EReadingMode::with_signed_temp(3, 51).is_err());
+        assert!(EReadingMode::with_signed_temp(3, -51).is_err());
+        assert!(EReadingMode::with_signed_temp(0, 0).is_err());
+    }
+
+    #[test]
+    fn test_ereading_valid_combinations() {
+        let valid: Vec<_> = EReadingMode::valid_combinations().collect();
+        assert!(valid.contains(&(3, 50)));
+        assert!(valid.contains(&(5, 100)));
+        assert!(valid.contains(&(3, 0)));
+        // Grayscale 1 at -50 encodes to 0 and decodes back to the same pair
+        assert!(valid.contains(&(1, 0)));
+        assert_eq!(AsusController::encode_monochrome(1, -50), 0);
+        assert_eq!(valid.len(), 5 * 101);
+
+        for (grayscale, temp) in valid {
+            let mode = EReadingMode::with_percent_temp(grayscale, temp).unwrap();
+            let value = AsusController::encode_monochrome(mode.grayscale, mode.temp);
+            assert_eq!(
+                AsusController::decode_monochrome(value),
+                (grayscale as i32, mode.temp as i32)
+            );
+        }
+    }
+
+    #[test]
+    fn test_monochrome_round_trip() {
+        for grayscale in 1..=5u8 {
+            for temp in EReadingMode::MIN_TEMP..=EReadingMode::MAX_TEMP {
+                let mode = EReadingMode::new(grayscale, temp).unwrap();
+                let value = AsusController::encode_monochrome(mode.grayscale, mode.temp);
+                assert_eq!(
+                    AsusController::decode_monochrome(value),
+                    (grayscale as i32, temp as i32),
+                    "value {}",
+                    value
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_float_dimming_percent() {
+        for splendid in 40..=100 {
+            let percent = AsusController::dimming_to_percent_f32(splendid);
+            assert_eq!(AsusController::percent_f32_to_dimming(percent), splendid);
+            assert_eq!(
+                percent.round() as i32,
+                AsusController::dimming_to_percent(splendid)
+            );
+        }
+        assert_eq!(AsusController::dimming_to_percent_f32(70), 50.0);
+        assert_eq!(AsusController::percent_f32_to_dimming(150.0), 100);
+        assert_eq!(AsusController::percent_f32_to_dimming(f32::NAN), 40);
+    }
+
+    #[test]
+    fn test_state_equality() {
+        let mock = MockController::new();
+        let before = mock.get_state();
+
+        mock.set_mode_if_changed(&NormalMode::new()).unwrap();
+        assert_eq!(mock.get_state(), before);
+
+        mock.set_mode(&VividMode::new()).unwrap();
+        assert_ne!(mock.get_state(), before);
+    }
+
+    #[test]
+    fn test_profile_capture_apply() {
+        let mock = MockController::new();
+        mock.set_mode(&EyeCareMode::new(3).unwrap()).unwrap();
+        mock.set_dimming_percent(60).unwrap();
+        let work = Profile::capture(&mock);
+        assert_eq!(work.mode, ModeSnapshot::EyeCare(3));
+        assert_eq!(work.dimming_percent, 60);
+
+        mock.set_mode(&VividMode::new()).unwrap();
+        mock.set_dimming_percent(100).unwrap();
+        work.apply(&mock).unwrap();
+        assert_eq!(Profile::capture(&mock), work);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_async_controller() {
+        let controller = AsyncController::new(MockController::new());
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        runtime.block_on(async {
+            controller.set_dimming_percent(50).await.unwrap();
+            assert_eq!(
+                controller.get_state().await.dimming,
+                AsusController::percent_to_dimming(50)
+            );
+            let mode = controller.toggle_e_reading().await.unwrap();
+            assert!(mode.is_ereading());
+            controller
+                .set_mode(Box::new(VividMode::new()))
+                .await
+                .unwrap();
+            assert_eq!(controller.get_current_mode().await.unwrap().mode_id(), 2);
+        });
+    }
+
+    #[test]
+    fn test_profile_dimming_bounds() {
+        let mock = MockController::new();
+        let mut reading = Profile::capture(&mock);
+        reading.dimming_percent = 90;
+        reading.dimming_bounds = Some(0..=70);
+
+        reading.apply(&mock).unwrap();
+        assert_eq!(
+            mock.get_state().dimming,
+            AsusController::percent_to_dimming(70)
+        );
+
+        // Set in splendid units, so rounding to a 10% step can't leave the bounds
+        mock.clear_calls();
+        reading.dimming_bounds = Some(0..=65);
+        reading.apply(&mock).unwrap();
+        let level = AsusController::percent_to_dimming(65);
+        assert!(mock.calls().contains(&MockCall::SetDimming(level)));
+        assert!(AsusController::dimming_to_percent(mock.get_state().dimming) <= 65);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_profile_store_export_import() {
+        let path = std::env::temp_dir().join(format!("azizo-store-{}", std::process::id()));
+        let mut store = ProfileStore::new();
+        store.insert(
+            "work",
+            Profile {
+                mode: ModeSnapshot::EyeCare(3),
+                dimming_percent: 60,
+                dimming_bounds: None,
+            },
+        );
+        store.insert(
+            "night reading",
+            Profile {
+                mode: ModeSnapshot::EReading {
+                    grayscale: 4,
+                    temp: -10,
+                },
+                dimming_percent: 20,
+                dimming_bounds: Some(0..=40),
+            },
+        );
+        store.export(&path).unwrap();
+        let imported = ProfileStore::import(&path);
+
+        let mut merged = ProfileStore::new();
+        let movie = Profile {
+            mode: ModeSnapshot::Vivid,
+            dimming_percent: 100,
+            dimming_bounds: None,
+        };
+        merged.insert("movie", movie.clone());
+        merged.insert("work", movie.clone());
+        merged.import_from(&path, ImportMode::Merge).unwrap();
+        let mut replaced = merged.clone();
+        replaced.import_from(&path, ImportMode::Replace).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(imported.unwrap(), store);
+        assert_eq!(merged.len(), 3);
+        assert_eq!(merged.get("movie"), Some(&movie));
+        assert_eq!(merged.get("work"), store.get("work"));
+        assert_eq!(replaced, store);
+
+        std::fs::write(&path, "{\"work\": {\"mode\": \"Normal\"}}").unwrap();
+        let invalid = ProfileStore::import(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(invalid, Err(ControllerError::Parse(_))));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_profile_save_load() {
+        let path = std::env::temp_dir().join(format!("azizo-profile-{}", std::process::id()));
+        let profile = Profile {
+            mode: ModeSnapshot::EReading {
+                grayscale: 4,
+                temp: -10,
+            },
+            dimming_percent: 45,
+            dimming_bounds: Some(20..=70),
+        };
+        profile.save_to_path(&path).unwrap();
+        let loaded = Profile::load_from_path(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.unwrap(), profile);
+    }
+
+    #[test]
+    fn test_mode_snapshot_text() {
+        for text in [
+            "normal",
+            "vivid",
+            "manual 50",
+            "eyecare 3",
+            "ereading 4 -10",
+        ] {
+            let mode: ModeSnapshot = text.parse().unwrap();
+            assert_eq!(mode.to_string(), text);
+        }
+        assert!("manual 300".parse::<ModeSnapshot>().is_err());
+        assert!("eyecare".parse::<ModeSnapshot>().is_err());
+        assert!("sepia".parse::<ModeSnapshot>().is_err());
+    }
+
+    #[test]
+    fn test_ereading_from_out_of_range_state() {
+        let state = ControllerState {
+            ereading_grayscale: 0,
+            ereading_temp: 0,
+            ..ControllerState::default()
+        };
+        let mode = EReadingMode::from_controller_state(&state);
+        assert_eq!(mode.grayscale, 1);
+        assert_eq!(
+            AsusController::encode_monochrome(mode.grayscale, mode.temp),
+            50
+        );
+
+        let mock = MockController::new();
+        mock.set_mode(&mode).unwrap();
+        assert_eq!(mock.get_state().ereading_grayscale, 1);
+
+        let state = ControllerState {
+            ereading_grayscale: 9,
+            ..state
+        };
+        assert_eq!(EReadingMode::from_controller_state(&state).grayscale, 5);
+    }
+
+    #[test]
+    fn test_ereading_temp_validation() {
+        assert!(EReadingMode::new(3, -50).is_ok());
+        assert!(EReadingMode::new(3, 50).is_ok());
+        assert!(matches!(
+            EReadingMode::new(3, 51),
+            Err(ControllerError::InvalidTemperature { value: 51, .. })
+        ));
+        assert!(EReadingMode::new(3, -51).is_err());
+        // Previously accepted and packed into the next grayscale level
+        assert!(EReadingMode::new(3, i8::MAX).is_err());
+        assert!(EReadingMode::new(3, i8::MIN).is_err());
+    }
+
+    #[test]
+    fn test_ereading_band() {
+        for grayscale in 1..=5u8 {
+            // The band is exactly the values decoded as this grayscale
+            let band = EReadingMode::band(grayscale);
+            let decoded = |value| AsusController::decode_monochrome(value).0;
+            assert_eq!(decoded(*band.start()), grayscale as i32);
+            assert_eq!(decoded(*band.end()), grayscale as i32);
+            assert_eq!(decoded(band.start() - 1), grayscale as i32 - 1);
+            assert_eq!(decoded(band.end() + 1), grayscale as i32 + 1);
+
+            // Every accepted temperature, negative ones included, stays in it,
+            // which is why EReadingMode::new only checks the temperature window
+            for temp in -128..=127i8 {
+                let value = AsusController::encode_monochrome(grayscale, temp);
+                if let Ok(mode) = EReadingMode::new(grayscale, temp) {
+                    assert!(band.contains(&value));
+                    assert_eq!(
+                        AsusController::decode_monochrome(value),
+                        (mode.grayscale as i32, mode.temp as i32)
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_mode_snapshot_from_display_mode() {
+        let snapshots = [
+            ModeSnapshot::Normal,
+            ModeSnapshot::Vivid,
+            ModeSnapshot::Manual(30),
+            ModeSnapshot::EyeCare(4),
+            ModeSnapshot::EReading {
+                grayscale: 2,
+                temp: -20,
+            },
+        ];
+        for snapshot in snapshots {
+            let mode = snapshot.to_mode().unwrap();
+            assert_eq!(ModeSnapshot::from(&*mode), snapshot);
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_state_serde_round_trip() {
+        let state = ControllerState {
+            mode_id: 7,
+            is_monochrome: true,
+            dimming: 82,
+            manual_slider: 35,
+            eyecare_level: 3,
+            ereading_grayscale: 2,
+            ereading_temp: -20,
+            last_non_ereading_mode: 7,
+            raw_callback: Some("7,0,1,82".to_string()),
+        };
+        let json = serde_json::to_string(&state).unwrap();
+        let restored: ControllerState = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, state);
+    }
+
+    #[test]
+    fn test_state_diff() {
+        let mock = MockController::new();
+        let target = mock.get_state();
+        assert_eq!(mock.get_state().diff(&target), None);
+
+        mock.set_mode(&VividMode::new()).unwrap();
+        mock.set_dimming(50).unwrap();
+        let diff = mock.get_state().diff(&target).unwrap();
+        assert_eq!(diff.mode_id, Some((1, 2)));
+        assert_eq!(diff.dimming, Some((70, 50)));
+        assert_eq!(diff.manual_slider, None);
+    }
+
+    #[test]
+    fn test_task_registry() {
+        use std::sync::Arc;
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        let tasks = TaskRegistry::new();
+        let stop = Arc::new(AtomicBool::new(false));
+        let id = tasks.register(TaskKind::StateLock, Arc::clone(&stop));
+        tasks.register(TaskKind::ThemeWatcher, Arc::new(AtomicBool::new(false)));
+        assert_eq!(tasks.active_tasks().len(), 2);
+        assert_eq!(tasks.active_tasks()[0].kind, TaskKind::StateLock);
+
+        assert!(tasks.stop(id));
+        assert!(stop.load(Ordering::SeqCst));
+        assert!(!tasks.stop(id));
+        assert_eq!(tasks.active_tasks().len(), 1);
+
+        // A waiting task is woken after its stop flag is set
+        let (wake, woken) = std::sync::mpsc::channel();
+        let stop = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&stop);
+        let id = tasks.register_with_waker(TaskKind::TemporaryMode, stop, move || {
+            let _ = wake.send(flag.load(Ordering::SeqCst));
+        });
+        assert!(tasks.stop(id));
+        assert_eq!(woken.try_recv(), Ok(true));
+    }
+
+    #[test]
+    fn test_wide_buffer_to_string() {
+        use crate::controller::wide_buffer_to_string;
+
+        let path = "C:\\Program Files\\WindowsApps\\B9ECED6F.ASUSPCAssistant";
+        let mut buffer: Vec<u16> = format!("{}\0", path).encode_utf16().collect();
+        let length = buffer.len() as u32;
+        buffer.extend([0x41, 0x42, 0]);
+
+        assert_eq!(wide_buffer_to_string(&buffer, length), path);
+        assert_eq!(wide_buffer_to_string(&buffer, 0), "");
+        assert_eq!(wide_buffer_to_string(&buffer[..3], length), "C:\\");
+    }
+
+    #[test]
+    fn test_mode_snapshot_from_state() {
+        let mut state = ControllerState {
+            mode_id: 6,
+            manual_slider: 30,
+            ereading_grayscale: 2,
+            ereading_temp: -20,
+            ..Default::default()
+        };
+        assert_eq!(ModeSnapshot::from_state(&state), ModeSnapshot::Manual(30));
+
+        state.is_monochrome = true;
+        assert_eq!(
+            ModeSnapshot::from_state(&state),
+            ModeSnapshot::EReading {
+                grayscale: 2,
+                temp: -20
+            }
+        );
+
+        state.is_monochrome = false;
+        state.mode_id = 42;
+        assert_eq!(ModeSnapshot::from_state(&state), ModeSnapshot::Unknown(42));
+    }
+
+    #[test]
+    fn test_mode_snapshot_to_mode() {
+        let mock = MockController::new();
+        for snapshot in [
+            ModeSnapshot::Vivid,
+            ModeSnapshot::EyeCare(3),
+            ModeSnapshot::EReading {
+                grayscale: 2,
+                temp: 0,
+            },
+        ] {
+            mock.set_mode(&*snapshot.to_mode().unwrap()).unwrap();
+            assert_eq!(ModeSnapshot::from_state(&mock.get_state()), snapshot);
+        }
+
+        assert!(matches!(
+            ModeSnapshot::Unknown(42).to_mode(),
+            Err(ControllerError::UnknownMode(42))
+        ));
+        assert!(ModeSnapshot::Manual(101).to_mode().is_err());
+    }
+
+    #[test]
+    fn test_dimming_overrides() {
+        let mock = MockController::new();
+        mock.set_base_dimming_percent(80).unwrap();
+        assert_eq!(mock.get_state().dimming, 88);
+
+        mock.push_dimming_override(30).unwrap();
+        assert_eq!(mock.get_state().dimming, 58);
+
+        // User changes while overridden take effect once the override is popped
+        mock.set_base_dimming_percent(100).unwrap();
+        assert_eq!(mock.get_state().dimming, 58);
+        assert_eq!(mock.base_dimming_percent(), Some(100));
+
+        mock.pop_dimming_override().unwrap();
+        assert_eq!(mock.get_state().dimming, 100);
+        mock.pop_dimming_override().unwrap();
+        assert_eq!(mock.get_state().dimming, 100);
+    }
+
+    #[test]
+    fn test_nits_to_dimming() {
+        assert_eq!(AsusController::nits_to_dimming(400.0, 400.0), 100);
+        assert_eq!(AsusController::nits_to_dimming(280.0, 400.0), 70);
+        assert_eq!(AsusController::nits_to_dimming(50.0, 400.0), 40);
+        assert_eq!(AsusController::nits_to_dimming(1000.0, 400.0), 100);
+        assert_eq!(AsusController::nits_to_dimming(300.0, 600.0), 50);
+    }
+
+    #[test]
+    fn test_automation_guard_defers_latest_change() {
+        use std::sync::Arc;
+
+        let mock = Arc::new(MockController::new());
+        let guard = AutomationGuard::new();
+        guard.pause();
+
+        let vivid = Arc::clone(&mock);
+        guard.run("test", move || vivid.set_mode(&VividMode::new()).unwrap());
+        let eyecare = Arc::clone(&mock);
+        guard.run("test", move || {
+            eyecare.set_mode(&EyeCareMode::new(2).unwrap()).unwrap()
+        });
+        assert_eq!(mock.get_state().mode_id, 1);
+
+        guard.resume();
+        assert_eq!(mock.get_state().mode_id, 7);
+    }
+
+    #[test]
+    fn test_automation_guard_pause_for() {
+        let guard = AutomationGuard::new();
+        guard.pause_for(std::time::Duration::from_millis(20));
+        assert!(guard.is_paused());
+        std::thread::sleep(std::time::Duration::from_millis(200));
+        assert!(!guard.is_paused());
+
+        // A manual pause outlasts a timed one
+        guard.pause();
+        guard.pause_for(std::time::Duration::from_millis(1));
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        assert!(guard.is_paused());
+    }
+
+    #[test]
+    fn test_mode_current_param() {
+        assert_eq!(NormalMode::new().current_param(), None);
+        assert_eq!(VividMode::new().param_range(), None);
+
+        let manual = ManualMode::new(30).unwrap();
+        assert_eq!(manual.current_param(), Some(30));
+        assert_eq!(manual.param_range(), Some(0..=100));
+
+        let eyecare = EyeCareMode::new(3).unwrap();
+        assert_eq!(eyecare.current_param(), Some(3));
+        assert_eq!(eyecare.param_range(), Some(0..=4));
+
+        let ereading = EReadingMode::new(2, 0).unwrap();
+        assert_eq!(ereading.current_param(), Some(2));
+        assert_eq!(ereading.param_range(), Some(1..=5));
+    }
+
+    #[test]
+    fn test_mode_callback_payload_parse() {
+        let payload = ModeCallbackPayload::parse("0_1_0_1_1,70,1");
+        assert_eq!(payload.unknown_0, Some(0));
+        assert_eq!(payload.unknown_4, Some(1));
+        assert_eq!(payload.dimming, Some(70));
+        assert_eq!(payload.monochrome, Some(true));
+
+        let partial = ModeCallbackPayload::parse("null");
+        assert_eq!(partial, ModeCallbackPayload::default());
+    }
+
+    #[test]
+    fn test_mode_from_state() {
+        let mut state = ControllerState {
+            mode_id: 7,
+            eyecare_level: 3,
+            ..Default::default()
+        };
+        let mode = mode_from_state(&state).unwrap();
+        assert_eq!(mode.mode_id(), 7);
+        assert_eq!(mode.current_param(), Some(3));
+
+        state.is_monochrome = true;
+        assert!(mode_from_state(&state).unwrap().is_ereading());
+
+        state.is_monochrome = false;
+        state.mode_id = 42;
+        assert!(matches!(
+            mode_from_state(&state),
+            Err(ControllerError::ModeNotDetected)
+        ));
+    }
+
+    #[test]
+    fn test_state_as_percentages() {
+        let state = ControllerState {
+            dimming: 70,
+            manual_slider: 30,
+            eyecare_level: 3,
+            ereading_grayscale: 5,
+            ereading_temp: -50,
+            ..Default::default()
+        };
+
+        let percents = state.as_percentages();
+        assert_eq!(
+            percents,
+            SliderPercents {
+                dimming: 50,
+                manual: 30,
+                eyecare: 75,
+                ereading_grayscale: 100,
+                ereading_temp: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_mode_kind_to_mode() {
+        let state = MockController::new().get_state();
+
+        let mode = ModeKind::EyeCare.to_mode(Some(4), &state).unwrap();
+        assert_eq!(mode.mode_id(), 7);
+        assert_eq!(mode.current_param(), Some(4));
+
+        let mode = ModeKind::Manual.to_mode(None, &state).unwrap();
+        assert_eq!(mode.current_param(), Some(state.manual_slider));
+
+        assert!(ModeKind::EyeCare.to_mode(Some(5), &state).is_err());
+        assert!(ModeKind::EReading.to_mode(Some(0), &state).is_err());
+    }
+
+    #[test]
+    fn test_mode_kind_ids_and_names() {
+        let state = MockController::new().get_state();
+        for kind in [
+            ModeKind::Normal,
+            ModeKind::Vivid,
+            ModeKind::Manual,
+            ModeKind::EyeCare,
+            ModeKind::EReading,
+        ] {
+            assert_eq!(ModeKind::from_mode_id(kind.mode_id()), Some(kind));
+            let mode = kind.to_mode(None, &state).unwrap();
+            assert_eq!(mode.mode_id(), kind.mode_id());
+            assert_eq!(mode.name(), kind.name());
+        }
+        assert_eq!(ModeKind::from_mode_id(3), None);
+    }
+
+    #[test]
+    fn test_needs_apply() {
+        let eyecare = EyeCareMode::new(2).unwrap();
+        assert!(!eyecare.needs_apply(&EyeCareMode::new(2).unwrap()));
+        assert!(eyecare.needs_apply(&EyeCareMode::new(3).unwrap()));
+        assert!(eyecare.needs_apply(&NormalMode::new()));
+
+        let ereading = EReadingMode::new(4, 0).unwrap();
+        assert!(!ereading.needs_apply(&EReadingMode::new(4, 0).unwrap()));
+        assert!(ereading.needs_apply(&EReadingMode::new(4, 10).unwrap()));
+        assert!(ereading.needs_apply(&NormalMode::new()));
+
+        let mock = MockController::new();
+        assert!(!mock.set_mode_if_changed(&NormalMode::new()).unwrap());
+        assert!(mock.set_mode_if_changed(&VividMode::new()).unwrap());
+    }
+
+    #[test]
+    fn test_mode_name() {
+        assert_eq!(mode_name(1), "Normal");
+        assert_eq!(mode_name(7), "Eye Care");
+        assert_eq!(mode_name(-1), "Unknown");
+    }
+
+    #[test]
+    fn test_clamped_constructors() {
+        assert_eq!(ManualMode::clamped(-20).value, 0);
+        assert_eq!(ManualMode::clamped(300).value, 100);
+        assert_eq!(ManualMode::clamped(42).value, 42);
+
+        assert_eq!(EyeCareMode::clamped(-1).level, 0);
+        assert_eq!(EyeCareMode::clamped(9).level, 4);
+    }
+
     #[test]
     fn test_mode_from_controller_state() {
         let state = ControllerState {
@@ -0,0 +1,99 @@
+//! Re-assert a target state when other utilities override it.
+
+use crate::automation::TaskKind;
+use crate::controller::{AsusController, DisplayController};
+use crate::modes::{self, DisplayMode};
+use crate::state::ControllerState;
+
+use log::info;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Number of consecutive polls drift must be observed before the target is re-applied.
+const DEBOUNCE_POLLS: u32 = 2;
+
+/// Handle to an active state lock.
+///
+/// Obtain with [`AsusController::lock_state`]. Enforcement stops when the handle
+/// is released or dropped.
+pub struct LockHandle {
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl LockHandle {
+    pub(crate) fn spawn(controller: Arc<AsusController>, target: ControllerState) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = Arc::clone(&stop);
+        let tasks = controller.task_registry();
+        let task_id = tasks.register(TaskKind::StateLock, Arc::clone(&stop));
+
+        let handle = std::thread::spawn(move || {
+            let target_mode = modes::mode_from_state(&target).ok();
+            let mut seen = 0;
+
+            while !thread_stop.load(Ordering::SeqCst) {
+                if drifted(&target, target_mode.as_deref(), &controller.get_state()) {
+                    seen += 1;
+                } else {
+                    seen = 0;
+                }
+
+                if seen >= DEBOUNCE_POLLS {
+                    info!("display settings drifted from the locked state, re-applying");
+                    if let Err(e) = controller.restore_state(&target) {
+                        controller.report_error(&e);
+                    }
+                    seen = 0;
+                }
+                std::thread::sleep(POLL_INTERVAL);
+            }
+
+            tasks.unregister(task_id);
+        });
+
+        Self {
+            stop,
+            handle: Some(handle),
+        }
+    }
+
+    /// Stop enforcing the locked state and wait for the background thread to exit.
+    pub fn release(mut self) {
+        self.shutdown();
+    }
+
+    fn shutdown(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for LockHandle {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+/// Whether `current` differs from `target` in a way re-applying it would fix.
+///
+/// Only the active mode (with its parameters) and the dimming are enforced;
+/// sliders of inactive modes are ignored.
+fn drifted(
+    target: &ControllerState,
+    target_mode: Option<&dyn DisplayMode>,
+    current: &ControllerState,
+) -> bool {
+    let mode_drifted = match (target_mode, modes::mode_from_state(current)) {
+        (Some(target_mode), Ok(current_mode)) => target_mode.needs_apply(&*current_mode),
+        (Some(_), Err(_)) => true,
+        (None, _) => false,
+    };
+    mode_drifted || (target.dimming >= 40 && current.dimming != target.dimming)
+}
@@ -2,15 +2,56 @@
 
 use crate::controller::{AsusController, DisplayController};
 use crate::error::ControllerError;
-use crate::modes::{DisplayMode, EReadingMode, EyeCareMode, ManualMode, NormalMode, VividMode};
-use crate::state::ControllerState;
+use crate::modes::{
+    DisplayMode, EReadingMode, EyeCareMode, ManualMode, ModeSnapshot, NormalMode, VividMode,
+    mode_from_state,
+};
+use crate::state::{ControllerState, DimmingLayers};
+use std::collections::VecDeque;
 use std::sync::Mutex;
+use std::time::Duration;
+
+/// A command received by a [`MockController`], see [`MockController::calls`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MockCall {
+    /// [`DisplayController::refresh_sliders`].
+    RefreshSliders,
+    /// [`DisplayController::sync_all_sliders`].
+    Sync,
+    /// [`DisplayController::set_dimming`] with the requested level.
+    SetDimming(i32),
+    /// [`DisplayController::set_dimming_percent`] with the requested percentage.
+    SetDimmingPercent(i32),
+    /// [`DisplayController::fade_dimming_percent_steps`] with the target percentage.
+    FadeDimmingPercent(i32),
+    /// [`DisplayController::set_base_dimming_percent`] with the requested percentage.
+    SetBaseDimmingPercent(i32),
+    /// [`DisplayController::push_dimming_override`] with the requested percentage.
+    PushDimmingOverride(i32),
+    /// [`DisplayController::pop_dimming_override`].
+    PopDimmingOverride,
+    /// [`DisplayController::set_mode`] with the mode's ID.
+    SetMode(i32),
+    /// [`DisplayController::toggle_e_reading`].
+    ToggleEReading,
+}
 
 /// A mock display controller for testing.
 ///
 /// This allows testing code that depends on [`DisplayController`] without
 /// requiring actual ASUS hardware or the ASUS DLL.
 ///
+/// Every command is recorded as a [`MockCall`], so tests can check which
+/// methods were called and in what order. Each call is recorded once, even if
+/// the mock implements it through another command; provided trait methods
+/// such as [`adjust_dimming_percent`](DisplayController::adjust_dimming_percent)
+/// show up as the commands they call. Queries are not recorded.
+///
+/// Errors can be queued with [`fail_next`](MockController::fail_next),
+/// [`fail_mode`](MockController::fail_mode) and
+/// [`fail_dimming`](MockController::fail_dimming) to exercise error handling.
+/// A failing command is still recorded but leaves the state untouched.
+///
 /// # Example
 ///
 /// ```
@@ -22,6 +63,17 @@ use std::sync::Mutex;
 /// ```
 pub struct MockController {
     state: Mutex<ControllerState>,
+    dimming_layers: Mutex<DimmingLayers>,
+    calls: Mutex<Vec<MockCall>>,
+    failures: Mutex<Failures>,
+}
+
+/// Errors queued for upcoming calls.
+#[derive(Default)]
+struct Failures {
+    next: VecDeque<ControllerError>,
+    mode: VecDeque<ControllerError>,
+    dimming: VecDeque<ControllerError>,
 }
 
 impl MockController {
@@ -37,7 +89,11 @@ impl MockController {
                 ereading_grayscale: 4,
                 ereading_temp: 0,
                 last_non_ereading_mode: 1,
+                raw_callback: None,
             }),
+            dimming_layers: Mutex::new(DimmingLayers::default()),
+            calls: Mutex::new(Vec::new()),
+            failures: Mutex::new(Failures::default()),
         }
     }
 
@@ -45,6 +101,89 @@ impl MockController {
     pub fn with_state(state: ControllerState) -> Self {
         Self {
             state: Mutex::new(state),
+            dimming_layers: Mutex::new(DimmingLayers::default()),
+            calls: Mutex::new(Vec::new()),
+            failures: Mutex::new(Failures::default()),
+        }
+    }
+
+    /// Commands received so far, oldest first.
+    pub fn calls(&self) -> Vec<MockCall> {
+        self.calls.lock().unwrap().clone()
+    }
+
+    /// Forget the recorded commands.
+    pub fn clear_calls(&self) {
+        self.calls.lock().unwrap().clear();
+    }
+
+    /// Make the next call that returns a `Result` fail with `err`.
+    ///
+    /// Errors queued this way are used up one per call, in order, before any
+    /// queued with [`fail_mode`](Self::fail_mode) or
+    /// [`fail_dimming`](Self::fail_dimming).
+    pub fn fail_next(&self, err: ControllerError) {
+        self.failures.lock().unwrap().next.push_back(err);
+    }
+
+    /// Make the next mode change ([`set_mode`](DisplayController::set_mode) or
+    /// [`toggle_e_reading`](DisplayController::toggle_e_reading)) fail with `err`.
+    pub fn fail_mode(&self, err: ControllerError) {
+        self.failures.lock().unwrap().mode.push_back(err);
+    }
+
+    /// Make the next dimming change fail with `err`.
+    ///
+    /// Applies to every dimming setter, including the override stack.
+    pub fn fail_dimming(&self, err: ControllerError) {
+        self.failures.lock().unwrap().dimming.push_back(err);
+    }
+
+    /// Record a command and return the error queued for it, if any.
+    fn record(&self, call: MockCall) -> Result<(), ControllerError> {
+        self.calls.lock().unwrap().push(call);
+        self.query()?;
+        let mut failures = self.failures.lock().unwrap();
+        let targeted = match call {
+            MockCall::SetMode(_) | MockCall::ToggleEReading => failures.mode.pop_front(),
+            MockCall::SetDimming(_)
+            | MockCall::SetDimmingPercent(_)
+            | MockCall::FadeDimmingPercent(_)
+            | MockCall::SetBaseDimmingPercent(_)
+            | MockCall::PushDimmingOverride(_)
+            | MockCall::PopDimmingOverride => failures.dimming.pop_front(),
+            MockCall::RefreshSliders | MockCall::Sync => None,
+        };
+        targeted.map_or(Ok(()), Err)
+    }
+
+    /// Return the error queued with [`fail_next`](Self::fail_next), if any.
+    fn query(&self) -> Result<(), ControllerError> {
+        let queued = self.failures.lock().unwrap().next.pop_front();
+        queued.map_or(Ok(()), Err)
+    }
+
+    fn store_dimming(&self, level: i32) {
+        self.state.lock().unwrap().dimming = level.clamp(40, 100);
+    }
+
+    fn store_mode(&self, mode: &dyn DisplayMode) {
+        let mut state = self.state.lock().unwrap();
+        if mode.is_ereading() {
+            state.last_non_ereading_mode = state.mode_id;
+            state.is_monochrome = true;
+        } else {
+            state.mode_id = mode.mode_id();
+            state.is_monochrome = false;
+        }
+        match mode.snapshot() {
+            ModeSnapshot::Manual(value) => state.manual_slider = value,
+            ModeSnapshot::EyeCare(level) => state.eyecare_level = level,
+            ModeSnapshot::EReading { grayscale, temp } => {
+                state.ereading_grayscale = grayscale;
+                state.ereading_temp = temp;
+            }
+            ModeSnapshot::Normal | ModeSnapshot::Vivid | ModeSnapshot::Unknown(_) => {}
         }
     }
 }
@@ -60,49 +199,88 @@ impl DisplayController for MockController {
         self.state.lock().unwrap().clone()
     }
 
+    fn state_blocking(&self, _timeout: Duration) -> Result<ControllerState, ControllerError> {
+        self.query()?;
+        Ok(self.get_state())
+    }
+
     fn refresh_sliders(&self) -> Result<(), ControllerError> {
+        self.record(MockCall::RefreshSliders)?;
         Ok(())
     }
 
     fn sync_all_sliders(&self) -> Result<(), ControllerError> {
+        self.record(MockCall::Sync)?;
         Ok(())
     }
 
     fn set_dimming(&self, level: i32) -> Result<(), ControllerError> {
-        self.state.lock().unwrap().dimming = level.clamp(40, 100);
+        self.record(MockCall::SetDimming(level))?;
+        self.store_dimming(level);
         Ok(())
     }
 
     fn set_dimming_percent(&self, percent: i32) -> Result<(), ControllerError> {
-        let splendid_value = AsusController::percent_to_dimming(percent.clamp(0, 100));
-        self.set_dimming(splendid_value)
+        self.record(MockCall::SetDimmingPercent(percent))?;
+        self.store_dimming(AsusController::percent_to_dimming(percent.clamp(0, 100)));
+        Ok(())
     }
 
-    fn get_current_mode(&self) -> Result<Box<dyn DisplayMode>, ControllerError> {
-        let state = self.get_state();
-        match (state.mode_id, state.is_monochrome) {
-            (1, false) => Ok(Box::new(NormalMode::new())),
-            (2, false) => Ok(Box::new(VividMode::new())),
-            (6, false) => Ok(Box::new(ManualMode::from_controller_state(&state))),
-            (7, false) => Ok(Box::new(EyeCareMode::from_controller_state(&state))),
-            (_, true) => Ok(Box::new(EReadingMode::from_controller_state(&state))),
-            _ => Err(ControllerError::ModeNotDetected),
+    fn fade_dimming_percent_steps(
+        &self,
+        target: i32,
+        _duration: Duration,
+        _steps: u32,
+    ) -> Result<(), ControllerError> {
+        self.record(MockCall::FadeDimmingPercent(target))?;
+        self.store_dimming(AsusController::percent_to_dimming(target.clamp(0, 100)));
+        Ok(())
+    }
+
+    fn set_base_dimming_percent(&self, percent: i32) -> Result<(), ControllerError> {
+        self.record(MockCall::SetBaseDimmingPercent(percent))?;
+        let level = AsusController::percent_to_dimming(percent.clamp(0, 100));
+        if let Some(level) = self.dimming_layers.lock().unwrap().set_base(level) {
+            self.store_dimming(level);
         }
+        Ok(())
     }
 
-    fn set_mode(&self, mode: &dyn DisplayMode) -> Result<(), ControllerError> {
-        let mut state = self.state.lock().unwrap();
-        if mode.is_ereading() {
-            state.last_non_ereading_mode = state.mode_id;
-            state.is_monochrome = true;
-        } else {
-            state.mode_id = mode.mode_id();
-            state.is_monochrome = false;
+    fn push_dimming_override(&self, percent: i32) -> Result<(), ControllerError> {
+        self.record(MockCall::PushDimmingOverride(percent))?;
+        let level = AsusController::percent_to_dimming(percent.clamp(0, 100));
+        let current = self.get_state().dimming;
+        let level = self.dimming_layers.lock().unwrap().push(level, current);
+        self.store_dimming(level);
+        Ok(())
+    }
+
+    fn pop_dimming_override(&self) -> Result<(), ControllerError> {
+        self.record(MockCall::PopDimmingOverride)?;
+        if let Some(level) = self.dimming_layers.lock().unwrap().pop() {
+            self.store_dimming(level);
         }
         Ok(())
     }
 
+    fn base_dimming_percent(&self) -> Option<i32> {
+        let layers = self.dimming_layers.lock().unwrap();
+        layers.base().map(AsusController::dimming_to_percent)
+    }
+
+    fn get_current_mode(&self) -> Result<Box<dyn DisplayMode>, ControllerError> {
+        self.query()?;
+        mode_from_state(&self.get_state())
+    }
+
+    fn set_mode(&self, mode: &dyn DisplayMode) -> Result<(), ControllerError> {
+        self.record(MockCall::SetMode(mode.mode_id()))?;
+        self.store_mode(mode);
+        Ok(())
+    }
+
     fn toggle_e_reading(&self) -> Result<Box<dyn DisplayMode>, ControllerError> {
+        self.record(MockCall::ToggleEReading)?;
         let state = self.get_state();
         if state.is_monochrome {
             let restored: Box<dyn DisplayMode> = match state.last_non_ereading_mode {
@@ -111,11 +289,11 @@ impl DisplayController for MockController {
                 7 => Box::new(EyeCareMode::from_controller_state(&state)),
                 _ => Box::new(NormalMode::new()),
             };
-            self.set_mode(&*restored)?;
+            self.store_mode(&*restored);
             Ok(restored)
         } else {
             let ereading = Box::new(EReadingMode::from_controller_state(&state));
-            self.set_mode(&*ereading)?;
+            self.store_mode(&*ereading);
             Ok(ereading)
         }
     }
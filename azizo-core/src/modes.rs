@@ -4,6 +4,10 @@ use crate::controller::AsusController;
 use crate::error::ControllerError;
 use crate::state::ControllerState;
 
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
 /// Trait for display mode implementations.
 ///
 /// Each display mode knows how to apply itself to the ASUS controller.
@@ -14,6 +18,15 @@ pub trait DisplayMode: std::fmt::Debug + Send + Sync {
     /// Get the RPC symbol name for setting this mode.
     fn symbol(&self) -> &'static [u8];
 
+    /// DLL symbols this mode needs to apply.
+    ///
+    /// [`AsusController`] checks these before calling [`apply`](Self::apply), so a
+    /// missing export is reported by name instead of failing mid-apply. The
+    /// default is empty, meaning nothing is checked up front.
+    fn required_symbols(&self) -> &'static [&'static [u8]] {
+        &[]
+    }
+
     /// Whether this is an e-reading/monochrome mode.
     fn is_ereading(&self) -> bool {
         false
@@ -21,6 +34,308 @@ pub trait DisplayMode: std::fmt::Debug + Send + Sync {
 
     /// Get the mode ID for this mode (used for state tracking).
     fn mode_id(&self) -> i32;
+
+    /// Human-readable name of the mode, for UI labels.
+    ///
+    /// One of `"Normal"`, `"Vivid"`, `"Manual"`, `"Eye Care"` or `"E-Reading"`
+    /// for the built-in modes. The default looks up [`mode_id`](Self::mode_id)
+    /// with [`mode_name`], so other modes get `"Unknown"` unless they override it.
+    fn name(&self) -> &'static str {
+        if self.is_ereading() {
+            "E-Reading"
+        } else {
+            mode_name(self.mode_id())
+        }
+    }
+
+    /// Current value of this mode's adjustable parameter, if it has one.
+    ///
+    /// This is the Manual value, the Eye Care level or the E-Reading grayscale.
+    fn current_param(&self) -> Option<u8> {
+        None
+    }
+
+    /// Valid range of this mode's adjustable parameter, if it has one.
+    fn param_range(&self) -> Option<RangeInclusive<u8>> {
+        None
+    }
+
+    /// Describe this mode as a plain value, for pattern matching and comparison.
+    ///
+    /// The default maps [`mode_id`](Self::mode_id) and
+    /// [`current_param`](Self::current_param) to the matching variant, and
+    /// anything else to [`ModeSnapshot::Unknown`].
+    fn snapshot(&self) -> ModeSnapshot {
+        match (self.mode_id(), self.current_param()) {
+            (1, _) => ModeSnapshot::Normal,
+            (2, _) => ModeSnapshot::Vivid,
+            (6, Some(value)) => ModeSnapshot::Manual(value),
+            (7, Some(level)) => ModeSnapshot::EyeCare(level),
+            (mode_id, _) => ModeSnapshot::Unknown(mode_id),
+        }
+    }
+
+    /// Uniform, human-readable description including parameters, for log lines.
+    ///
+    /// For example `"Vivid"`, `"Manual (50)"` or `"E-Reading (grayscale 4, temp 0)"`.
+    fn describe(&self) -> String {
+        match self.current_param() {
+            Some(param) => format!("{} ({})", self.name(), param),
+            None => self.name().to_string(),
+        }
+    }
+
+    /// Whether switching from `current` to this mode requires an RPC call.
+    ///
+    /// Returns `false` when both modes have the same
+    /// [`snapshot`](Self::snapshot), which covers every parameter of the
+    /// built-in modes. Modes without a snapshot variant
+    /// ([`ModeSnapshot::Unknown`]) also compare their
+    /// [`current_param`](Self::current_param).
+    fn needs_apply(&self, current: &dyn DisplayMode) -> bool {
+        match (self.snapshot(), current.snapshot()) {
+            (ModeSnapshot::Unknown(_), ModeSnapshot::Unknown(_)) => {
+                self.mode_id() != current.mode_id()
+                    || self.current_param() != current.current_param()
+            }
+            (target, current) => target != current,
+        }
+    }
+}
+
+/// Interpret a controller state snapshot as the display mode it describes.
+///
+/// E-reading takes precedence over the underlying mode when monochrome is active.
+///
+/// # Errors
+///
+/// Returns [`ControllerError::ModeNotDetected`] if the mode ID is unknown.
+pub fn mode_from_state(state: &ControllerState) -> Result<Box<dyn DisplayMode>, ControllerError> {
+    match (state.mode_id, state.is_monochrome) {
+        (1, false) => Ok(Box::new(NormalMode::new())),
+        (2, false) => Ok(Box::new(VividMode::new())),
+        (6, false) => Ok(Box::new(ManualMode::from_controller_state(state))),
+        (7, false) => Ok(Box::new(EyeCareMode::from_controller_state(state))),
+        (_, true) => Ok(Box::new(EReadingMode::from_controller_state(state))),
+        _ => Err(ControllerError::ModeNotDetected),
+    }
+}
+
+/// An owned, data-only description of a display mode with its parameters.
+///
+/// Unlike `Box<dyn DisplayMode>`, this is easy to match on, compare, store and
+/// send over IPC. With the `serde` feature it implements `Serialize` and
+/// `Deserialize`. Convert from a mode with `ModeSnapshot::from(&*mode)` or
+/// [`DisplayMode::snapshot`], and back with [`to_mode`](Self::to_mode).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ModeSnapshot {
+    /// [`NormalMode`].
+    Normal,
+    /// [`VividMode`].
+    Vivid,
+    /// [`ManualMode`] with its value (0-100).
+    Manual(u8),
+    /// [`EyeCareMode`] with its level (0-4).
+    EyeCare(u8),
+    /// [`EReadingMode`].
+    EReading {
+        /// Grayscale level (1-5).
+        grayscale: u8,
+        /// Temperature value (-50 to +50, 0 is neutral).
+        temp: i8,
+    },
+    /// A mode ID this crate does not recognize.
+    Unknown(i32),
+}
+
+impl ModeSnapshot {
+    /// Describe the mode in a controller state snapshot.
+    ///
+    /// E-reading takes precedence over the underlying mode when monochrome is active.
+    pub fn from_state(state: &ControllerState) -> Self {
+        match (state.mode_id, state.is_monochrome) {
+            (_, true) => ModeSnapshot::EReading {
+                grayscale: state.ereading_grayscale,
+                temp: state.ereading_temp,
+            },
+            (1, false) => ModeSnapshot::Normal,
+            (2, false) => ModeSnapshot::Vivid,
+            (6, false) => ModeSnapshot::Manual(state.manual_slider),
+            (7, false) => ModeSnapshot::EyeCare(state.eyecare_level),
+            (mode_id, false) => ModeSnapshot::Unknown(mode_id),
+        }
+    }
+
+    /// Build the display mode this snapshot describes.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ControllerError::UnknownMode`] for [`ModeSnapshot::Unknown`] and
+    /// [`ControllerError::InvalidSliderValue`] if a parameter is out of range.
+    pub fn to_mode(&self) -> Result<Box<dyn DisplayMode>, ControllerError> {
+        Ok(match *self {
+            ModeSnapshot::Normal => Box::new(NormalMode::new()),
+            ModeSnapshot::Vivid => Box::new(VividMode::new()),
+            ModeSnapshot::Manual(value) => Box::new(ManualMode::new(value)?),
+            ModeSnapshot::EyeCare(level) => Box::new(EyeCareMode::new(level)?),
+            ModeSnapshot::EReading { grayscale, temp } => {
+                Box::new(EReadingMode::new(grayscale, temp)?)
+            }
+            ModeSnapshot::Unknown(mode_id) => return Err(ControllerError::UnknownMode(mode_id)),
+        })
+    }
+}
+
+impl From<&dyn DisplayMode> for ModeSnapshot {
+    fn from(mode: &dyn DisplayMode) -> Self {
+        mode.snapshot()
+    }
+}
+
+/// Formats as the mode keyword followed by its parameters, e.g. `"normal"`,
+/// `"eyecare 3"` or `"ereading 4 -10"`. Parsed back by the [`FromStr`] impl.
+impl fmt::Display for ModeSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ModeSnapshot::Normal => write!(f, "normal"),
+            ModeSnapshot::Vivid => write!(f, "vivid"),
+            ModeSnapshot::Manual(value) => write!(f, "manual {}", value),
+            ModeSnapshot::EyeCare(level) => write!(f, "eyecare {}", level),
+            ModeSnapshot::EReading { grayscale, temp } => {
+                write!(f, "ereading {} {}", grayscale, temp)
+            }
+            ModeSnapshot::Unknown(mode_id) => write!(f, "unknown {}", mode_id),
+        }
+    }
+}
+
+impl FromStr for ModeSnapshot {
+    type Err = ControllerError;
+
+    /// Parse the format written by the [`Display`](fmt::Display) impl.
+    ///
+    /// Keywords are case-insensitive. Parameter ranges are not checked here;
+    /// [`ModeSnapshot::to_mode`] validates them.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ControllerError::Parse(format!("invalid mode: {:?}", s));
+        let mut parts = s.split_whitespace();
+        let keyword = parts.next().ok_or_else(invalid)?.to_ascii_lowercase();
+
+        let snapshot = match keyword.as_str() {
+            "normal" => ModeSnapshot::Normal,
+            "vivid" => ModeSnapshot::Vivid,
+            "manual" => ModeSnapshot::Manual(next_param(&mut parts).ok_or_else(invalid)?),
+            "eyecare" => ModeSnapshot::EyeCare(next_param(&mut parts).ok_or_else(invalid)?),
+            "ereading" => ModeSnapshot::EReading {
+                grayscale: next_param(&mut parts).ok_or_else(invalid)?,
+                temp: next_param(&mut parts).ok_or_else(invalid)?,
+            },
+            "unknown" => ModeSnapshot::Unknown(next_param(&mut parts).ok_or_else(invalid)?),
+            _ => return Err(invalid()),
+        };
+        if parts.next().is_some() {
+            return Err(invalid());
+        }
+        Ok(snapshot)
+    }
+}
+
+fn next_param<'a, T: FromStr>(parts: &mut impl Iterator<Item = &'a str>) -> Option<T> {
+    parts.next()?.parse().ok()
+}
+
+/// Get the UI label for a mode ID reported in [`ControllerState::mode_id`].
+///
+/// Returns `"Normal"`, `"Vivid"`, `"Manual"`, `"Eye Care"` or `"Unknown"`.
+pub fn mode_name(mode_id: i32) -> &'static str {
+    match mode_id {
+        1 => "Normal",
+        2 => "Vivid",
+        6 => "Manual",
+        7 => "Eye Care",
+        _ => "Unknown",
+    }
+}
+
+/// The kind of a display mode, without its parameters.
+///
+/// Useful for flat, data-driven APIs such as CLIs and config files. See
+/// [`AsusController::set_mode_kind`](crate::AsusController::set_mode_kind).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ModeKind {
+    /// [`NormalMode`].
+    Normal,
+    /// [`VividMode`].
+    Vivid,
+    /// [`ManualMode`].
+    Manual,
+    /// [`EyeCareMode`].
+    EyeCare,
+    /// [`EReadingMode`].
+    EReading,
+}
+
+impl ModeKind {
+    /// The kind with the given mode ID, as reported by
+    /// [`DisplayMode::mode_id`] or in [`ControllerState::mode_id`].
+    ///
+    /// Returns `None` for IDs this crate does not recognize.
+    pub fn from_mode_id(mode_id: i32) -> Option<Self> {
+        match mode_id {
+            1 => Some(ModeKind::Normal),
+            2 => Some(ModeKind::Vivid),
+            6 => Some(ModeKind::Manual),
+            7 => Some(ModeKind::EyeCare),
+            -1 => Some(ModeKind::EReading),
+            _ => None,
+        }
+    }
+
+    /// The mode ID of this kind, `-1` for E-Reading.
+    pub fn mode_id(self) -> i32 {
+        match self {
+            ModeKind::Normal => 1,
+            ModeKind::Vivid => 2,
+            ModeKind::Manual => 6,
+            ModeKind::EyeCare => 7,
+            ModeKind::EReading => -1,
+        }
+    }
+
+    /// The UI label of this kind, matching [`DisplayMode::name`].
+    pub fn name(self) -> &'static str {
+        match self {
+            ModeKind::EReading => "E-Reading",
+            kind => mode_name(kind.mode_id()),
+        }
+    }
+
+    /// Build a mode of this kind.
+    ///
+    /// `param` is the Manual value, Eye Care level or E-Reading grayscale and is
+    /// ignored for Normal and Vivid. When it is `None`, the value from `state` is
+    /// used. E-Reading always takes its temperature from `state`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ControllerError::InvalidSliderValue`] if `param` is out of range.
+    pub fn to_mode(
+        self,
+        param: Option<u8>,
+        state: &ControllerState,
+    ) -> Result<Box<dyn DisplayMode>, ControllerError> {
+        Ok(match self {
+            ModeKind::Normal => Box::new(NormalMode::new()),
+            ModeKind::Vivid => Box::new(VividMode::new()),
+            ModeKind::Manual => Box::new(ManualMode::new(param.unwrap_or(state.manual_slider))?),
+            ModeKind::EyeCare => Box::new(EyeCareMode::new(param.unwrap_or(state.eyecare_level))?),
+            ModeKind::EReading => Box::new(EReadingMode::new(
+                param.unwrap_or(state.ereading_grayscale),
+                state.ereading_temp,
+            )?),
+        })
+    }
 }
 
 // =============================================================================
@@ -53,9 +368,17 @@ impl DisplayMode for NormalMode {
         b"MyOptSetSplendidFunc"
     }
 
+    fn required_symbols(&self) -> &'static [&'static [u8]] {
+        &[b"MyOptSetSplendidFunc"]
+    }
+
     fn mode_id(&self) -> i32 {
         1
     }
+
+    fn name(&self) -> &'static str {
+        "Normal"
+    }
 }
 
 // =============================================================================
@@ -88,9 +411,17 @@ impl DisplayMode for VividMode {
         b"MyOptSetSplendidFunc"
     }
 
+    fn required_symbols(&self) -> &'static [&'static [u8]] {
+        &[b"MyOptSetSplendidFunc"]
+    }
+
     fn mode_id(&self) -> i32 {
         2
     }
+
+    fn name(&self) -> &'static str {
+        "Vivid"
+    }
 }
 
 // =============================================================================
@@ -121,6 +452,13 @@ impl ManualMode {
         Ok(Self { value })
     }
 
+    /// Create a Manual mode, clamping `value` into 0-100 instead of erroring.
+    pub fn clamped(value: i32) -> Self {
+        Self {
+            value: value.clamp(0, 100) as u8,
+        }
+    }
+
     /// Create from a controller state snapshot.
     pub fn from_controller_state(state: &ControllerState) -> Self {
         Self {
@@ -138,9 +476,25 @@ impl DisplayMode for ManualMode {
         b"MyOptSetSplendidManualFunc"
     }
 
+    fn required_symbols(&self) -> &'static [&'static [u8]] {
+        &[b"MyOptSetSplendidManualFunc"]
+    }
+
     fn mode_id(&self) -> i32 {
         6
     }
+
+    fn name(&self) -> &'static str {
+        "Manual"
+    }
+
+    fn current_param(&self) -> Option<u8> {
+        Some(self.value)
+    }
+
+    fn param_range(&self) -> Option<RangeInclusive<u8>> {
+        Some(0..=100)
+    }
 }
 
 // =============================================================================
@@ -155,22 +509,49 @@ pub struct EyeCareMode {
 }
 
 impl EyeCareMode {
+    /// Weakest blue light filter level.
+    pub const MIN_LEVEL: u8 = 0;
+
+    /// Strongest blue light filter level (maximum warmth).
+    pub const MAX_LEVEL: u8 = 4;
+
     /// Create a new Eye Care mode with the specified level.
     ///
     /// # Errors
     /// Returns an error if level > 4.
     pub fn new(level: u8) -> Result<Self, ControllerError> {
-        if level > 4 {
+        if level > Self::MAX_LEVEL {
             return Err(ControllerError::InvalidSliderValue {
                 mode: "EyeCare",
                 value: level,
-                min: 0,
-                max: 4,
+                min: Self::MIN_LEVEL,
+                max: Self::MAX_LEVEL,
             });
         }
         Ok(Self { level })
     }
 
+    /// Create an Eye Care mode, clamping `level` into 0-4 instead of erroring.
+    pub fn clamped(level: i32) -> Self {
+        Self {
+            level: level.clamp(Self::MIN_LEVEL as i32, Self::MAX_LEVEL as i32) as u8,
+        }
+    }
+
+    /// Create an Eye Care mode with the weakest filter level.
+    pub fn min() -> Self {
+        Self {
+            level: Self::MIN_LEVEL,
+        }
+    }
+
+    /// Create an Eye Care mode with the strongest filter level.
+    pub fn max() -> Self {
+        Self {
+            level: Self::MAX_LEVEL,
+        }
+    }
+
     /// Create from a controller state snapshot.
     pub fn from_controller_state(state: &ControllerState) -> Self {
         Self {
@@ -188,9 +569,25 @@ impl DisplayMode for EyeCareMode {
         b"MyOptSetSplendidEyecareFunc"
     }
 
+    fn required_symbols(&self) -> &'static [&'static [u8]] {
+        &[b"MyOptSetSplendidEyecareFunc"]
+    }
+
     fn mode_id(&self) -> i32 {
         7
     }
+
+    fn name(&self) -> &'static str {
+        "Eye Care"
+    }
+
+    fn current_param(&self) -> Option<u8> {
+        Some(self.level)
+    }
+
+    fn param_range(&self) -> Option<RangeInclusive<u8>> {
+        Some(0..=4)
+    }
 }
 
 // =============================================================================
@@ -198,6 +595,12 @@ impl DisplayMode for EyeCareMode {
 // =============================================================================
 
 /// E-Reading display mode - grayscale with adjustable temperature.
+///
+/// The temperature is stored signed (-50 to +50, 0 is neutral), which is what the
+/// device encodes as `grayscale * 256 + temp - 206`. The GUI and other
+/// user-facing code may use a 0-100 scale instead, where 50 is neutral; see
+/// [`with_percent_temp`](Self::with_percent_temp) and
+/// [`percent_temp`](Self::percent_temp).
 #[derive(Debug, Clone, Copy)]
 pub struct EReadingMode {
     /// Grayscale level (1-5).
@@ -207,16 +610,27 @@ pub struct EReadingMode {
 }
 
 impl EReadingMode {
+    /// Lowest signed temperature.
+    pub const MIN_TEMP: i8 = -50;
+    /// Highest signed temperature.
+    pub const MAX_TEMP: i8 = 50;
+
     /// Create a new E-Reading mode.
     ///
     /// # Arguments
     /// * `grayscale` - Grayscale level (1-5)
-    /// * `temp` - Temperature value (-50 to +50)
+    /// * `temp` - Temperature value (-50 to +50, 0 is neutral)
+    ///
+    /// The temperature window matches the GUI slider. Any temperature in it
+    /// packs into the grayscale's [`band`](Self::band), so the DLL value is read
+    /// back as the same pair and needs no further check.
     ///
     /// # Errors
-    /// Returns an error if grayscale is not in range 1-5.
+    /// Returns [`ControllerError::InvalidSliderValue`] if grayscale is not in range
+    /// 1-5 and [`ControllerError::InvalidTemperature`] if temp is not in range
+    /// -50 to +50.
     pub fn new(grayscale: u8, temp: i8) -> Result<Self, ControllerError> {
-        if grayscale < 1 || grayscale > 5 {
+        if !(1..=5).contains(&grayscale) {
             return Err(ControllerError::InvalidSliderValue {
                 mode: "EReading grayscale",
                 value: grayscale,
@@ -224,13 +638,80 @@ impl EReadingMode {
                 max: 5,
             });
         }
+        if !(Self::MIN_TEMP..=Self::MAX_TEMP).contains(&temp) {
+            return Err(ControllerError::InvalidTemperature {
+                value: temp as i32,
+                min: Self::MIN_TEMP as i32,
+                max: Self::MAX_TEMP as i32,
+            });
+        }
         Ok(Self { grayscale, temp })
     }
 
+    /// The encoded DLL values that belong to `grayscale`.
+    ///
+    /// Each level owns the 256 values centred on its neutral temperature, which
+    /// are exactly those the state decoding reads back as `grayscale`.
+    /// Neighbouring bands don't overlap; a temperature that would push the
+    /// encoded value out of this range is read back as another grayscale level.
+    pub fn band(grayscale: u8) -> RangeInclusive<i32> {
+        let neutral = AsusController::encode_monochrome(grayscale, 0);
+        neutral - 128..=neutral + 127
+    }
+
+    /// Create an E-Reading mode with a signed temperature.
+    ///
+    /// Same as [`new`](Self::new); named for symmetry with
+    /// [`with_percent_temp`](Self::with_percent_temp).
+    pub fn with_signed_temp(grayscale: u8, temp: i8) -> Result<Self, ControllerError> {
+        Self::new(grayscale, temp)
+    }
+
+    /// Create an E-Reading mode with a temperature on the 0-100 scale.
+    ///
+    /// 0 maps to -50, 50 to neutral and 100 to +50.
+    ///
+    /// # Errors
+    /// Returns an error if grayscale is not in range 1-5 or temp is above 100.
+    pub fn with_percent_temp(grayscale: u8, temp: u8) -> Result<Self, ControllerError> {
+        if temp > 100 {
+            return Err(ControllerError::InvalidTemperature {
+                value: temp as i32,
+                min: 0,
+                max: 100,
+            });
+        }
+        Self::with_signed_temp(grayscale, temp as i8 - 50)
+    }
+
+    /// Get the temperature on the 0-100 scale (50 is neutral).
+    ///
+    /// Out-of-range signed temperatures are clamped.
+    pub fn percent_temp(&self) -> u8 {
+        (self.temp as i32 + 50).clamp(0, 100) as u8
+    }
+
+    /// List the `(grayscale, temp)` pairs the hardware accepts.
+    ///
+    /// Grayscale is 1-5 and temperature is on the 0-100 scale (50 is neutral).
+    /// These are exactly the pairs [`with_percent_temp`](Self::with_percent_temp)
+    /// accepts, each of which encodes to a DLL value that is reported back as
+    /// the same pair. That includes grayscale 1 at temperature 0, whose DLL
+    /// value is 0, the lowest one.
+    pub fn valid_combinations() -> impl Iterator<Item = (u8, u8)> {
+        (1..=5u8)
+            .flat_map(|grayscale| (0..=100u8).map(move |temp| (grayscale, temp)))
+            .filter(|&(grayscale, temp)| Self::with_percent_temp(grayscale, temp).is_ok())
+    }
+
     /// Create from a controller state snapshot.
+    ///
+    /// Grayscale is 1-5 both in the state and on the hardware. A state that was
+    /// never populated (or decoded from an odd DLL value) can hold 0 or more than
+    /// 5, so it is clamped into range rather than sent to the DLL as is.
     pub fn from_controller_state(state: &ControllerState) -> Self {
         Self {
-            grayscale: state.ereading_grayscale,
+            grayscale: state.ereading_grayscale.clamp(1, 5),
             temp: state.ereading_temp,
         }
     }
@@ -246,6 +727,10 @@ impl DisplayMode for EReadingMode {
         b"MyOptSetSplendidMonochromeFunc"
     }
 
+    fn required_symbols(&self) -> &'static [&'static [u8]] {
+        &[b"MyOptSetSplendidMonochromeFunc"]
+    }
+
     fn is_ereading(&self) -> bool {
         true
     }
@@ -253,4 +738,68 @@ impl DisplayMode for EReadingMode {
     fn mode_id(&self) -> i32 {
         -1 // Special case - e-reading doesn't have a single mode ID
     }
+
+    fn name(&self) -> &'static str {
+        "E-Reading"
+    }
+
+    fn current_param(&self) -> Option<u8> {
+        Some(self.grayscale)
+    }
+
+    fn param_range(&self) -> Option<RangeInclusive<u8>> {
+        Some(1..=5)
+    }
+
+    fn snapshot(&self) -> ModeSnapshot {
+        ModeSnapshot::EReading {
+            grayscale: self.grayscale,
+            temp: self.temp,
+        }
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "E-Reading (grayscale {}, temp {})",
+            self.grayscale, self.temp
+        )
+    }
+}
+
+/// Named e-reading presets for users who don't want to tune the raw sliders.
+///
+/// | Preset  | Grayscale | Temperature |
+/// |---------|-----------|-------------|
+/// | `Paper` | 3         | 0 (neutral) |
+/// | `Sepia` | 4         | +30         |
+/// | `Night` | 5         | +50         |
+///
+/// Positive temperatures are warmer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EReadingPreset {
+    /// Neutral gray, like printed paper.
+    Paper,
+    /// Warm tint for long reading sessions.
+    Sepia,
+    /// Strongest grayscale and warmest tint, for reading in the dark.
+    Night,
+}
+
+impl EReadingPreset {
+    /// All presets, in menu order.
+    pub const ALL: [EReadingPreset; 3] = [
+        EReadingPreset::Paper,
+        EReadingPreset::Sepia,
+        EReadingPreset::Night,
+    ];
+
+    /// The e-reading mode this preset stands for.
+    pub fn to_mode(&self) -> EReadingMode {
+        let (grayscale, temp) = match self {
+            EReadingPreset::Paper => (3, 0),
+            EReadingPreset::Sepia => (4, 30),
+            EReadingPreset::Night => (5, 50),
+        };
+        EReadingMode { grayscale, temp }
+    }
 }
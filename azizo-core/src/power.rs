@@ -0,0 +1,172 @@
+//! Dim the display while running on battery.
+
+use crate::automation::{TaskKind, WatcherOptions};
+use crate::controller::DisplayController;
+use crate::system;
+
+use log::info;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Number of consecutive polls a new power source must be observed before it is acted on.
+const DEBOUNCE_POLLS: u32 = 2;
+
+/// Dims the display while unplugged and restores the dimming on AC power.
+///
+/// A background thread polls the power source (every 2s by default, see
+/// [`WatcherOptions::poll_interval`]). When the laptop is unplugged, the battery
+/// dimming is pushed as a dimming override
+/// (see [`DisplayController::push_dimming_override`]). When it is plugged back
+/// in, the override is popped, restoring the user's base dimming. A
+/// change must be observed on consecutive polls before it is acted on, so brief
+/// power transitions are ignored. If the laptop is already on battery at start,
+/// the battery dimming is applied immediately.
+///
+/// Changes go through the [`AutomationGuard`](crate::AutomationGuard) given in the
+/// [`WatcherOptions`], so they are held back while automation is paused.
+///
+/// The thread stops when the watcher is stopped or dropped. If the battery
+/// override is pushed at that point, it is popped on the way out.
+///
+/// # Example
+///
+/// ```no_run
+/// use azizo_core::{AsusController, PowerWatcher};
+/// use std::sync::Arc;
+///
+/// let controller = Arc::new(AsusController::new()?);
+/// // Dim to 30% while on battery
+/// let watcher = PowerWatcher::spawn(controller, 30);
+/// // ...
+/// watcher.stop();
+/// # Ok::<(), azizo_core::ControllerError>(())
+/// ```
+pub struct PowerWatcher {
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl PowerWatcher {
+    /// Start watching the power source, dimming to `battery_percent` (0-100) on battery.
+    pub fn spawn(controller: Arc<dyn DisplayController>, battery_percent: i32) -> Self {
+        Self::spawn_with(controller, battery_percent, WatcherOptions::default())
+    }
+
+    /// Start watching the power source with custom options.
+    pub fn spawn_with(
+        controller: Arc<dyn DisplayController>,
+        battery_percent: i32,
+        options: WatcherOptions,
+    ) -> Self {
+        let poll_interval = options.poll_interval_or(DEFAULT_POLL_INTERVAL);
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = Arc::clone(&stop);
+        let task = options.tasks.as_ref().map(|tasks| {
+            (
+                tasks.clone(),
+                tasks.register(TaskKind::PowerWatcher, Arc::clone(&stop)),
+            )
+        });
+
+        let handle = std::thread::spawn(move || {
+            // Whether the battery override is currently pushed. Held while
+            // pushing or popping so the exit below can't miss a late push.
+            let overridden = Arc::new(Mutex::new(false));
+            let mut applied: Option<bool> = None;
+            let mut pending: Option<(bool, u32)> = None;
+
+            while !thread_stop.load(Ordering::SeqCst) {
+                if let Some(on_ac) = system::on_ac_power() {
+                    if applied.is_none() {
+                        // Only act at start if already unplugged
+                        if on_ac {
+                            applied = Some(true);
+                        } else {
+                            pending = Some((false, DEBOUNCE_POLLS));
+                        }
+                    } else if applied != Some(on_ac) {
+                        let seen = match pending {
+                            Some((source, count)) if source == on_ac => count + 1,
+                            _ => 1,
+                        };
+                        pending = Some((on_ac, seen));
+                    } else {
+                        pending = None;
+                    }
+
+                    if let Some((on_ac, count)) = pending
+                        && count >= DEBOUNCE_POLLS
+                    {
+                        let controller = Arc::clone(&controller);
+                        let overridden = Arc::clone(&overridden);
+                        let stopped = Arc::clone(&thread_stop);
+                        info!("power source is {}", if on_ac { "AC" } else { "battery" });
+                        options.guard.run("power", move || {
+                            let mut overridden = overridden.lock().unwrap();
+                            // A change held back by the guard may run after the watcher stopped
+                            if stopped.load(Ordering::SeqCst) {
+                                return;
+                            }
+                            let result = if on_ac == *overridden {
+                                *overridden = !on_ac;
+                                if on_ac {
+                                    controller.pop_dimming_override()
+                                } else {
+                                    controller.push_dimming_override(battery_percent)
+                                }
+                            } else {
+                                Ok(())
+                            };
+                            if let Err(e) = result {
+                                controller.report_error(&e);
+                            }
+                        });
+                        applied = Some(on_ac);
+                        pending = None;
+                    }
+                }
+                std::thread::sleep(poll_interval);
+            }
+
+            let mut overridden = overridden.lock().unwrap();
+            if std::mem::take(&mut *overridden) {
+                info!("power watcher stopped, removing the battery dimming");
+                if let Err(e) = controller.pop_dimming_override() {
+                    controller.report_error(&e);
+                }
+            }
+            drop(overridden);
+
+            if let Some((tasks, id)) = task {
+                tasks.unregister(id);
+            }
+        });
+
+        Self {
+            stop,
+            handle: Some(handle),
+        }
+    }
+
+    /// Stop watching and wait for the background thread to exit.
+    pub fn stop(mut self) {
+        self.shutdown();
+    }
+
+    fn shutdown(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for PowerWatcher {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
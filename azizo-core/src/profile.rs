@@ -0,0 +1,262 @@
+//! Named display presets that can be saved and restored.
+
+use crate::controller::{AsusController, DisplayController};
+use crate::error::ControllerError;
+use crate::modes::ModeSnapshot;
+
+use std::collections::BTreeMap;
+use std::ops::RangeInclusive;
+#[cfg(feature = "serde")]
+use std::{fs, path::Path};
+
+/// A display mode with a dimming level, e.g. a "work" or "movie" preset.
+///
+/// With the `serde` feature the struct implements `Serialize` and
+/// `Deserialize`, and [`save_to_path`](Self::save_to_path) and
+/// [`load_from_path`](Self::load_from_path) store it as JSON.
+///
+/// # Example
+///
+/// ```no_run
+/// use azizo_core::{AsusController, Profile};
+///
+/// let controller = AsusController::new()?;
+/// let work = Profile::capture(&controller);
+/// // Later...
+/// work.apply(&controller)?;
+/// # Ok::<(), azizo_core::ControllerError>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Profile {
+    /// The display mode with its parameters.
+    pub mode: ModeSnapshot,
+    /// Dimming level (0-100%).
+    pub dimming_percent: i32,
+    /// Inclusive percentage range the dimming is kept in, e.g. `0..=70` for a
+    /// reading profile that should never get bright.
+    ///
+    /// See [`AsusController::set_mode_constrained`].
+    pub dimming_bounds: Option<RangeInclusive<i32>>,
+}
+
+impl Profile {
+    /// Capture the current mode and dimming from the controller's cached state.
+    pub fn capture(controller: &dyn DisplayController) -> Self {
+        let state = controller.get_state();
+        Self {
+            mode: ModeSnapshot::from_state(&state),
+            dimming_percent: AsusController::dimming_to_percent(state.dimming),
+            dimming_bounds: None,
+        }
+    }
+
+    /// Apply the mode, then the dimming clamped into the
+    /// [`dimming_bounds`](Self::dimming_bounds).
+    ///
+    /// With bounds, the dimming is clamped and set in splendid units, as in
+    /// [`AsusController::set_mode_constrained`], so it can't be rounded back out
+    /// of them. The dimming is applied even if the mode fails.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error encountered.
+    pub fn apply(&self, controller: &dyn DisplayController) -> Result<(), ControllerError> {
+        let mode = self
+            .mode
+            .to_mode()
+            .and_then(|mode| controller.set_mode(&*mode));
+        let dimming = match &self.dimming_bounds {
+            Some(bounds) => {
+                let (min, max) = AsusController::splendid_bounds(bounds).into_inner();
+                let level = AsusController::percent_to_dimming(self.dimming_percent.clamp(0, 100));
+                controller.set_dimming(level.clamp(min, max))
+            }
+            None => controller.set_dimming_percent(self.dimming_percent),
+        };
+        mode.and(dimming)
+    }
+
+    /// Write the profile to a JSON file, replacing it if it exists.
+    ///
+    /// Requires the `serde` feature.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use azizo_core::{AsusController, Profile};
+    ///
+    /// let controller = AsusController::new()?;
+    /// Profile::capture(&controller).save_to_path("work.json")?;
+    /// // Later...
+    /// Profile::load_from_path("work.json")?.apply(&controller)?;
+    /// # Ok::<(), azizo_core::ControllerError>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ControllerError::Io`] if the file cannot be written.
+    #[cfg(feature = "serde")]
+    pub fn save_to_path(&self, path: impl AsRef<Path>) -> Result<(), ControllerError> {
+        let json = serde_json::to_string_pretty(self).map_err(std::io::Error::from)?;
+        fs::write(path, json)?;
+        Ok(())
+    }
+
+    /// Read a profile written by [`save_to_path`](Self::save_to_path).
+    ///
+    /// Requires the `serde` feature.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ControllerError::Io`] if the file cannot be read and
+    /// [`ControllerError::Parse`] if it is not a valid profile.
+    #[cfg(feature = "serde")]
+    pub fn load_from_path(path: impl AsRef<Path>) -> Result<Self, ControllerError> {
+        serde_json::from_str(&fs::read_to_string(path)?)
+            .map_err(|e| ControllerError::Parse(e.to_string()))
+    }
+}
+
+/// A set of named profiles that can be exported to and imported from one file.
+///
+/// For backing up profiles or moving them to another machine. With the `serde`
+/// feature, [`export`](Self::export) writes every profile to one JSON file,
+/// keyed by name, which [`import`](Self::import) reads back.
+///
+/// Profiles are kept sorted by name.
+///
+/// # Example
+///
+/// ```
+/// use azizo_core::{ModeSnapshot, Profile, ProfileStore};
+///
+/// let mut store = ProfileStore::new();
+/// store.insert(
+///     "movie",
+///     Profile {
+///         mode: ModeSnapshot::Vivid,
+///         dimming_percent: 100,
+///         dimming_bounds: None,
+///     },
+/// );
+/// assert_eq!(store.get("movie").unwrap().dimming_percent, 100);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+pub struct ProfileStore {
+    profiles: BTreeMap<String, Profile>,
+}
+
+/// How [`ProfileStore::import_from`] combines imported profiles with existing ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportMode {
+    /// Keep existing profiles; imported ones replace those with the same name.
+    Merge,
+    /// Drop all existing profiles first.
+    Replace,
+}
+
+impl ProfileStore {
+    /// Create an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a profile, returning the one it replaced under the same name.
+    pub fn insert(&mut self, name: impl Into<String>, profile: Profile) -> Option<Profile> {
+        self.profiles.insert(name.into(), profile)
+    }
+
+    /// Get a profile by name.
+    pub fn get(&self, name: &str) -> Option<&Profile> {
+        self.profiles.get(name)
+    }
+
+    /// Remove a profile by name.
+    pub fn remove(&mut self, name: &str) -> Option<Profile> {
+        self.profiles.remove(name)
+    }
+
+    /// Iterate over the profiles in name order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Profile)> {
+        self.profiles
+            .iter()
+            .map(|(name, profile)| (name.as_str(), profile))
+    }
+
+    /// Number of profiles in the store.
+    pub fn len(&self) -> usize {
+        self.profiles.len()
+    }
+
+    /// Whether the store has no profiles.
+    pub fn is_empty(&self) -> bool {
+        self.profiles.is_empty()
+    }
+
+    /// Write all profiles to a JSON file, replacing it if it exists.
+    ///
+    /// Requires the `serde` feature.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use azizo_core::{ImportMode, ProfileStore};
+    ///
+    /// let mut store = ProfileStore::import("backup.json")?;
+    /// store.import_from("laptop.json", ImportMode::Merge)?;
+    /// store.export("backup.json")?;
+    /// # Ok::<(), azizo_core::ControllerError>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ControllerError::Io`] if the file cannot be written.
+    #[cfg(feature = "serde")]
+    pub fn export(&self, path: impl AsRef<Path>) -> Result<(), ControllerError> {
+        let json = serde_json::to_string_pretty(self).map_err(std::io::Error::from)?;
+        fs::write(path, json)?;
+        Ok(())
+    }
+
+    /// Read a store written by [`export`](Self::export).
+    ///
+    /// Requires the `serde` feature.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ControllerError::Io`] if the file cannot be read and
+    /// [`ControllerError::Parse`] if it is not a valid profile store.
+    #[cfg(feature = "serde")]
+    pub fn import(path: impl AsRef<Path>) -> Result<Self, ControllerError> {
+        serde_json::from_str(&fs::read_to_string(path)?)
+            .map_err(|e| ControllerError::Parse(e.to_string()))
+    }
+
+    /// Add the profiles from an exported file to this store.
+    ///
+    /// The store is left unchanged if the file fails to load. Requires the
+    /// `serde` feature.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`import`](Self::import).
+    #[cfg(feature = "serde")]
+    pub fn import_from(
+        &mut self,
+        path: impl AsRef<Path>,
+        mode: ImportMode,
+    ) -> Result<(), ControllerError> {
+        let imported = Self::import(path)?;
+        if mode == ImportMode::Replace {
+            self.profiles.clear();
+        }
+        self.profiles.extend(imported.profiles);
+        Ok(())
+    }
+}
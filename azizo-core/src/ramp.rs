@@ -0,0 +1,259 @@
+//! Gradual color temperature changes through the day.
+
+use crate::controller::DisplayController;
+use crate::error::ControllerError;
+use crate::modes::{DisplayMode, EyeCareMode, ManualMode};
+use crate::system;
+
+use log::debug;
+use std::sync::Arc;
+use std::time::Duration;
+
+const HOUR: Duration = Duration::from_secs(60 * 60);
+const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Color temperature in Kelvin assumed for Manual value 0 (warmest).
+pub const MANUAL_MIN_KELVIN: u32 = 3000;
+
+/// Color temperature in Kelvin assumed for Manual value 100 (coolest).
+pub const MANUAL_MAX_KELVIN: u32 = 10000;
+
+/// Continuously shifts the Manual color temperature between a day and a night
+/// value, like Redshift.
+///
+/// The temperature is fully at the day value between the sunrise and sunset
+/// transitions and fully at the night value outside them. Each transition is
+/// centered on sunrise or sunset and interpolates linearly in Kelvin. Sunset
+/// may be earlier than sunrise, e.g. for night shifts, in which case the day
+/// runs past midnight.
+///
+/// Splendid does not document the Kelvin of Manual values, so a linear scale
+/// from [`MANUAL_MIN_KELVIN`] (value 0) to [`MANUAL_MAX_KELVIN`] (value 100) is
+/// assumed. The interpolated temperature is snapped to the nearest Manual value
+/// and only applied when that value changes.
+///
+/// Call [`tick_now`](Self::tick_now) periodically, e.g. once a minute from a
+/// scheduler loop.
+///
+/// # Example
+///
+/// ```no_run
+/// use azizo_core::{AsusController, DayRamp};
+/// use std::sync::Arc;
+/// use std::time::Duration;
+///
+/// let controller = Arc::new(AsusController::new()?);
+/// let ramp = DayRamp::new(controller, 6500, 3400)
+///     .sunset(Duration::from_secs(20 * 60 * 60));
+/// loop {
+///     ramp.tick_now()?;
+///     std::thread::sleep(Duration::from_secs(60));
+/// }
+/// # Ok::<(), azizo_core::ControllerError>(())
+/// ```
+pub struct DayRamp {
+    controller: Arc<dyn DisplayController>,
+    day_kelvin: u32,
+    night_kelvin: u32,
+    schedule: Schedule,
+}
+
+/// Sunrise, sunset and the transition window shared by the ramps.
+#[derive(Debug, Clone, Copy)]
+struct Schedule {
+    sunrise: Duration,
+    sunset: Duration,
+    transition: Duration,
+}
+
+impl Default for Schedule {
+    /// Sunrise at 07:00, sunset at 19:00 and one-hour transitions.
+    fn default() -> Self {
+        Self {
+            sunrise: 7 * HOUR,
+            sunset: 19 * HOUR,
+            transition: HOUR,
+        }
+    }
+}
+
+impl Schedule {
+    /// How much of the day value applies at `now`: 1.0 during the day, 0.0 at
+    /// night, and linear in between within each transition window.
+    ///
+    /// Times wrap around midnight, so sunset may come before sunrise and a
+    /// transition window may span midnight.
+    fn day_fraction(&self, now: Duration) -> f32 {
+        let day = DAY.as_secs_f32();
+        let wrap = |secs: f32| secs.rem_euclid(day);
+        let sunrise = self.sunrise.as_secs_f32();
+        let since_sunrise = wrap(now.as_secs_f32() - sunrise);
+        let day_length = wrap(self.sunset.as_secs_f32() - sunrise);
+        let half = self.transition.as_secs_f32() / 2.0;
+        if half == 0.0 {
+            return if since_sunrise < day_length { 1.0 } else { 0.0 };
+        }
+
+        // Distance to the nearest of sunrise and sunset, negative at night
+        let into_day = if since_sunrise <= day_length {
+            since_sunrise.min(day_length - since_sunrise)
+        } else {
+            -(since_sunrise - day_length).min(day - since_sunrise)
+        };
+        ((into_day + half) / (2.0 * half)).clamp(0.0, 1.0)
+    }
+}
+
+impl DayRamp {
+    /// Create a ramp between a day and a night color temperature in Kelvin.
+    ///
+    /// Sunrise defaults to 07:00, sunset to 19:00 and the transitions to one hour.
+    pub fn new(controller: Arc<dyn DisplayController>, day_kelvin: u32, night_kelvin: u32) -> Self {
+        Self {
+            controller,
+            day_kelvin,
+            night_kelvin,
+            schedule: Schedule::default(),
+        }
+    }
+
+    /// Set the time of sunrise, as time since local midnight.
+    pub fn sunrise(mut self, time_of_day: Duration) -> Self {
+        self.schedule.sunrise = time_of_day;
+        self
+    }
+
+    /// Set the time of sunset, as time since local midnight.
+    pub fn sunset(mut self, time_of_day: Duration) -> Self {
+        self.schedule.sunset = time_of_day;
+        self
+    }
+
+    /// Set the length of the transition window centered on sunrise and sunset.
+    pub fn transition(mut self, window: Duration) -> Self {
+        self.schedule.transition = window;
+        self
+    }
+
+    /// Interpolated color temperature at `now`, given as time since local midnight.
+    pub fn kelvin_at(&self, now: Duration) -> u32 {
+        let day_fraction = self.schedule.day_fraction(now);
+        let day = self.day_kelvin as f32;
+        let night = self.night_kelvin as f32;
+        (night + (day - night) * day_fraction).round() as u32
+    }
+
+    /// Manual value (0-100) closest to the color temperature at `now`.
+    pub fn manual_value_at(&self, now: Duration) -> u8 {
+        let kelvin = self
+            .kelvin_at(now)
+            .clamp(MANUAL_MIN_KELVIN, MANUAL_MAX_KELVIN);
+        let fraction =
+            (kelvin - MANUAL_MIN_KELVIN) as f32 / (MANUAL_MAX_KELVIN - MANUAL_MIN_KELVIN) as f32;
+        (fraction * 100.0).round() as u8
+    }
+
+    /// Apply the Manual value for `now`, given as time since local midnight.
+    ///
+    /// Nothing is sent if the display is already in Manual mode with that value.
+    pub fn tick(&self, now: Duration) -> Result<ManualMode, ControllerError> {
+        let mode = ManualMode::clamped(self.manual_value_at(now) as i32);
+        if self.controller.set_mode_if_changed(&mode)? {
+            debug!("day ramp applied {}", mode.describe());
+        }
+        Ok(mode)
+    }
+
+    /// Apply the Manual value for the current local time.
+    pub fn tick_now(&self) -> Result<ManualMode, ControllerError> {
+        self.tick(system::local_time_of_day())
+    }
+}
+
+/// Raises the Eye Care level as evening approaches and lowers it in the morning.
+///
+/// Uses the same schedule as [`DayRamp`]: the day level between the sunrise and
+/// sunset transitions, the night level outside them, and a linear interpolation
+/// within each transition, snapped to the nearest level. Eye Care only has five
+/// levels, so the interpolation steps through each intermediate level in turn
+/// instead of jumping straight from day to night; a longer
+/// [`transition`](Self::transition) spaces the steps further apart.
+///
+/// # Example
+///
+/// ```no_run
+/// use azizo_core::{AsusController, EyeCareRamp};
+/// use std::sync::Arc;
+/// use std::time::Duration;
+///
+/// let controller = Arc::new(AsusController::new()?);
+/// let ramp = EyeCareRamp::new(controller, 0, 4).transition(Duration::from_secs(2 * 60 * 60));
+/// loop {
+///     ramp.tick_now()?;
+///     std::thread::sleep(Duration::from_secs(60));
+/// }
+/// # Ok::<(), azizo_core::ControllerError>(())
+/// ```
+pub struct EyeCareRamp {
+    controller: Arc<dyn DisplayController>,
+    day_level: u8,
+    night_level: u8,
+    schedule: Schedule,
+}
+
+impl EyeCareRamp {
+    /// Create a ramp between a day and a night Eye Care level (0-4).
+    ///
+    /// Levels above 4 are clamped. Sunrise defaults to 07:00, sunset to 19:00
+    /// and the transitions to one hour.
+    pub fn new(controller: Arc<dyn DisplayController>, day_level: u8, night_level: u8) -> Self {
+        Self {
+            controller,
+            day_level: day_level.min(EyeCareMode::MAX_LEVEL),
+            night_level: night_level.min(EyeCareMode::MAX_LEVEL),
+            schedule: Schedule::default(),
+        }
+    }
+
+    /// Set the time of sunrise, as time since local midnight.
+    pub fn sunrise(mut self, time_of_day: Duration) -> Self {
+        self.schedule.sunrise = time_of_day;
+        self
+    }
+
+    /// Set the time of sunset, as time since local midnight.
+    pub fn sunset(mut self, time_of_day: Duration) -> Self {
+        self.schedule.sunset = time_of_day;
+        self
+    }
+
+    /// Set the length of the transition window centered on sunrise and sunset.
+    pub fn transition(mut self, window: Duration) -> Self {
+        self.schedule.transition = window;
+        self
+    }
+
+    /// Eye Care level at `now`, given as time since local midnight.
+    pub fn level_at(&self, now: Duration) -> u8 {
+        let day_fraction = self.schedule.day_fraction(now);
+        let day = self.day_level as f32;
+        let night = self.night_level as f32;
+        (night + (day - night) * day_fraction).round() as u8
+    }
+
+    /// Apply the Eye Care level for `now`, given as time since local midnight.
+    ///
+    /// Nothing is sent if the display is already in Eye Care mode at that level.
+    pub fn tick(&self, now: Duration) -> Result<EyeCareMode, ControllerError> {
+        let mode = EyeCareMode::clamped(self.level_at(now) as i32);
+        if self.controller.set_mode_if_changed(&mode)? {
+            debug!("eye care ramp applied {}", mode.describe());
+        }
+        Ok(mode)
+    }
+
+    /// Apply the Eye Care level for the current local time.
+    pub fn tick_now(&self) -> Result<EyeCareMode, ControllerError> {
+        self.tick(system::local_time_of_day())
+    }
+}
@@ -0,0 +1,176 @@
+//! Suggest a stronger Eye Care level after long stretches of screen use.
+
+use crate::automation::{TaskKind, WatcherOptions};
+use crate::modes::EyeCareMode;
+use crate::system;
+
+use log::debug;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Continuous activity, reset by a long enough break.
+struct Usage {
+    idle_threshold: Duration,
+    active_since: Option<Instant>,
+}
+
+impl Usage {
+    fn record_idle(&mut self, idle: Duration, now: Instant) {
+        if idle >= self.idle_threshold {
+            if self.active_since.take().is_some() {
+                debug!("screen time reset after {:?} idle", idle);
+            }
+        } else if self.active_since.is_none() {
+            self.active_since = Some(now.checked_sub(idle).unwrap_or(now));
+        }
+    }
+
+    fn active_time(&self, now: Instant) -> Duration {
+        self.active_since
+            .map(|since| now.saturating_duration_since(since))
+            .unwrap_or_default()
+    }
+}
+
+/// Tracks continuous screen time and recommends an Eye Care level for it.
+///
+/// Time counts as active until there has been no keyboard or mouse input for
+/// the idle threshold, which starts the count over. The recommended level goes
+/// up by one for every [`RAMP_STEP`](Self::RAMP_STEP) of continuous use, up to
+/// the highest Eye Care level. The tracker only recommends; the app decides
+/// whether to prompt or apply it.
+///
+/// [`spawn`](Self::spawn) polls the time since the last input (every 5s by
+/// default, see [`WatcherOptions::poll_interval`]) on a background thread,
+/// which stops when the tracker is stopped or dropped. Apps with their own
+/// idle detection can use [`new`](Self::new) and feed it through
+/// [`record_idle`](Self::record_idle) instead.
+///
+/// # Example
+///
+/// ```no_run
+/// use azizo_core::{AsusController, DisplayController, EyeCareMode, ScreenTimeTracker};
+/// use std::time::Duration;
+///
+/// let controller = AsusController::new()?;
+/// // A five-minute break resets the count
+/// let tracker = ScreenTimeTracker::spawn(Duration::from_secs(5 * 60));
+/// // Later, e.g. from a timer
+/// let level = tracker.recommended_eyecare_level();
+/// if level > 0 {
+///     controller.set_mode(&EyeCareMode::new(level)?)?;
+/// }
+/// # Ok::<(), azizo_core::ControllerError>(())
+/// ```
+pub struct ScreenTimeTracker {
+    usage: Arc<Mutex<Usage>>,
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl ScreenTimeTracker {
+    /// Continuous use per step of the recommended Eye Care level.
+    pub const RAMP_STEP: Duration = Duration::from_secs(30 * 60);
+
+    /// Create a tracker without a polling thread, fed by [`record_idle`](Self::record_idle).
+    pub fn new(idle_threshold: Duration) -> Self {
+        Self {
+            usage: Arc::new(Mutex::new(Usage {
+                idle_threshold,
+                active_since: None,
+            })),
+            stop: Arc::new(AtomicBool::new(false)),
+            handle: None,
+        }
+    }
+
+    /// Start tracking, treating `idle_threshold` without input as a break.
+    pub fn spawn(idle_threshold: Duration) -> Self {
+        Self::spawn_with(idle_threshold, WatcherOptions::default())
+    }
+
+    /// Start tracking with custom options.
+    ///
+    /// The tracker doesn't change the display, so the options' guard is unused.
+    pub fn spawn_with(idle_threshold: Duration, options: WatcherOptions) -> Self {
+        let mut tracker = Self::new(idle_threshold);
+        let poll_interval = options.poll_interval_or(DEFAULT_POLL_INTERVAL);
+        let usage = Arc::clone(&tracker.usage);
+        let thread_stop = Arc::clone(&tracker.stop);
+        let task = options.tasks.as_ref().map(|tasks| {
+            (
+                tasks.clone(),
+                tasks.register(TaskKind::ScreenTimeTracker, Arc::clone(&tracker.stop)),
+            )
+        });
+
+        tracker.handle = Some(std::thread::spawn(move || {
+            while !thread_stop.load(Ordering::SeqCst) {
+                if let Some(idle) = system::idle_time() {
+                    usage.lock().unwrap().record_idle(idle, Instant::now());
+                }
+                std::thread::sleep(poll_interval);
+            }
+
+            if let Some((tasks, id)) = task {
+                tasks.unregister(id);
+            }
+        }));
+        tracker
+    }
+
+    /// Report the time since the last user input.
+    ///
+    /// Starts counting if the user wasn't active yet, and resets the count if
+    /// `idle` reaches the idle threshold.
+    pub fn record_idle(&self, idle: Duration) {
+        self.usage.lock().unwrap().record_idle(idle, Instant::now());
+    }
+
+    /// Continuous screen time since the last break.
+    pub fn active_time(&self) -> Duration {
+        self.usage.lock().unwrap().active_time(Instant::now())
+    }
+
+    /// Start the count over, e.g. after the user dismissed a prompt.
+    pub fn reset(&self) {
+        self.usage.lock().unwrap().active_since = None;
+    }
+
+    /// The Eye Care level (0-4) suggested for the current screen time.
+    ///
+    /// See [`level_for`](Self::level_for).
+    pub fn recommended_eyecare_level(&self) -> u8 {
+        Self::level_for(self.active_time())
+    }
+
+    /// The Eye Care level (0-4) suggested after `active` of continuous use.
+    ///
+    /// 0 for the first [`RAMP_STEP`](Self::RAMP_STEP), then one level per step.
+    pub fn level_for(active: Duration) -> u8 {
+        let steps = active.as_secs() / Self::RAMP_STEP.as_secs();
+        steps.min(EyeCareMode::MAX_LEVEL as u64) as u8
+    }
+
+    /// Stop tracking and wait for the background thread to exit.
+    pub fn stop(mut self) {
+        self.shutdown();
+    }
+
+    fn shutdown(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for ScreenTimeTracker {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
@@ -0,0 +1,83 @@
+//! Optional trace file of callbacks and DLL operations for field debugging.
+//!
+//! This is independent of the `log` crate so users can capture a self-contained
+//! trace without configuring a logger.
+
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Size after which the log file is rotated to `<path>.1`.
+const MAX_LOG_BYTES: u64 = 1024 * 1024;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static SESSION_LOG: Mutex<Option<SessionLog>> = Mutex::new(None);
+
+struct SessionLog {
+    path: PathBuf,
+    file: File,
+    written: u64,
+}
+
+impl SessionLog {
+    fn open(path: PathBuf) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let written = file.metadata()?.len();
+        Ok(Self {
+            path,
+            file,
+            written,
+        })
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        let mut rotated = self.path.clone().into_os_string();
+        rotated.push(".1");
+        fs::rename(&self.path, rotated)?;
+        *self = Self::open(self.path.clone())?;
+        Ok(())
+    }
+
+    fn write_line(&mut self, args: fmt::Arguments) -> io::Result<()> {
+        if self.written >= MAX_LOG_BYTES {
+            self.rotate()?;
+        }
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        let line = format!("[{}.{:03}] {}\n", now.as_secs(), now.subsec_millis(), args);
+        self.file.write_all(line.as_bytes())?;
+        self.written += line.len() as u64;
+        Ok(())
+    }
+}
+
+/// Start appending trace lines to `path`, replacing any previously enabled log.
+pub(crate) fn enable(path: PathBuf) -> io::Result<()> {
+    let log = SessionLog::open(path)?;
+    *SESSION_LOG.lock().unwrap() = Some(log);
+    ENABLED.store(true, Ordering::SeqCst);
+    Ok(())
+}
+
+/// Stop writing trace lines.
+pub(crate) fn disable() {
+    ENABLED.store(false, Ordering::SeqCst);
+    *SESSION_LOG.lock().unwrap() = None;
+}
+
+/// Append a timestamped line if logging is enabled.
+pub(crate) fn record(args: fmt::Arguments) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    if let Some(log) = SESSION_LOG.lock().unwrap().as_mut()
+        && let Err(e) = log.write_line(args)
+    {
+        log::warn!("failed to write session log: {}", e);
+    }
+}
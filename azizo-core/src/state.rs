@@ -1,12 +1,34 @@
 //! Controller state snapshot.
 
+use crate::controller::AsusController;
+use crate::modes::DisplayMode;
+
+use std::time::{Duration, Instant};
+
 /// A snapshot of the controller's current state.
 ///
 /// This captures all slider/mode values at a point in time.
 /// Use [`DisplayController::get_state`](crate::DisplayController::get_state) to obtain a snapshot.
-#[derive(Debug, Clone, Default)]
+/// With the `serde` feature it implements `Serialize` and `Deserialize`, so it
+/// can be saved to disk and restored later.
+///
+/// Every slider field uses the same scale as the matching mode constructor and
+/// the hardware; no offsets are applied between what is set and what is read
+/// back. Only [`dimming`](Self::dimming) differs from the user-facing
+/// percentage; see [`AsusController::dimming_to_percent`].
+///
+/// Equality compares every field, including the sliders of inactive modes and
+/// [`raw_callback`](Self::raw_callback), so two snapshots of the same visible
+/// settings can still differ.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ControllerState {
     /// The current mode ID (1=Normal, 2=Vivid, 6=Manual, 7=EyeCare).
+    ///
+    /// `-1` is reserved: it is the ID [`EReadingMode`](crate::EReadingMode)
+    /// reports and the value before any mode has been reported. E-reading itself
+    /// is indicated by [`is_monochrome`](Self::is_monochrome), so while it is
+    /// active this usually still holds the underlying mode.
     pub mode_id: i32,
     /// Whether monochrome/e-reading mode is active.
     pub is_monochrome: bool,
@@ -16,10 +38,207 @@ pub struct ControllerState {
     pub manual_slider: u8,
     /// Eye care mode level (0-4).
     pub eyecare_level: u8,
-    /// E-reading grayscale level (1-5).
+    /// E-reading grayscale level (1-5), the scale of
+    /// [`EReadingMode::grayscale`](crate::EReadingMode::grayscale).
+    ///
+    /// Setting grayscale 4 reads back 4, whatever the temperature.
     pub ereading_grayscale: u8,
-    /// E-reading temperature value (-50 to +50, 0 is neutral).
+    /// E-reading temperature value (-50 to +50, 0 is neutral), the scale of
+    /// [`EReadingMode::temp`](crate::EReadingMode::temp).
     pub ereading_temp: i8,
     /// The last non-e-reading mode ID (for restoration).
     pub last_non_ereading_mode: i32,
+    /// The full string of the last mode callback (func 18), as reported by the DLL.
+    ///
+    /// Only partly understood; see [`ModeCallbackPayload`](crate::ModeCallbackPayload)
+    /// for the parsed fields. `None` until the first mode callback.
+    pub raw_callback: Option<String>,
+}
+
+impl ControllerState {
+    /// Normalize every adjustable value to 0-100 for uniform display.
+    ///
+    /// The raw fields remain available on the state for precise control.
+    pub fn as_percentages(&self) -> SliderPercents {
+        let scale = |value: i32, min: i32, max: i32| {
+            ((value.clamp(min, max) - min) * 100 / (max - min)) as u8
+        };
+        SliderPercents {
+            dimming: AsusController::dimming_to_percent(self.dimming) as u8,
+            manual: scale(self.manual_slider as i32, 0, 100),
+            eyecare: scale(self.eyecare_level as i32, 0, 4),
+            ereading_grayscale: scale(self.ereading_grayscale as i32, 1, 5),
+            ereading_temp: scale(self.ereading_temp as i32, -50, 50),
+        }
+    }
+
+    /// Compare this state against a `target`, returning the fields that differ.
+    ///
+    /// `last_non_ereading_mode` is bookkeeping and not compared. Returns `None`
+    /// if the states match.
+    pub fn diff(&self, target: &ControllerState) -> Option<StateDiff> {
+        fn field<T: PartialEq>(target: T, actual: T) -> Option<(T, T)> {
+            (target != actual).then_some((target, actual))
+        }
+        let diff = StateDiff {
+            mode_id: field(target.mode_id, self.mode_id),
+            is_monochrome: field(target.is_monochrome, self.is_monochrome),
+            dimming: field(target.dimming, self.dimming),
+            manual_slider: field(target.manual_slider, self.manual_slider),
+            eyecare_level: field(target.eyecare_level, self.eyecare_level),
+            ereading_grayscale: field(target.ereading_grayscale, self.ereading_grayscale),
+            ereading_temp: field(target.ereading_temp, self.ereading_temp),
+        };
+        (diff != StateDiff::default()).then_some(diff)
+    }
+}
+
+/// Fields of a [`ControllerState`] that differ from a target, as
+/// `(target, actual)` pairs.
+///
+/// Obtain with [`ControllerState::diff`] or [`AsusController::drift_from`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StateDiff {
+    /// Mode ID.
+    pub mode_id: Option<(i32, i32)>,
+    /// Monochrome/e-reading flag.
+    pub is_monochrome: Option<(bool, bool)>,
+    /// Dimming in splendid units.
+    pub dimming: Option<(i32, i32)>,
+    /// Manual slider value.
+    pub manual_slider: Option<(u8, u8)>,
+    /// Eye care level.
+    pub eyecare_level: Option<(u8, u8)>,
+    /// E-reading grayscale.
+    pub ereading_grayscale: Option<(u8, u8)>,
+    /// E-reading temperature.
+    pub ereading_temp: Option<(i8, i8)>,
+}
+
+/// Adjustable values of a [`ControllerState`], each normalized to 0-100.
+///
+/// Obtain with [`ControllerState::as_percentages`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SliderPercents {
+    /// Dimming (40-100 splendid units).
+    pub dimming: u8,
+    /// Manual color temperature (0-100).
+    pub manual: u8,
+    /// Eye care level (0-4).
+    pub eyecare: u8,
+    /// E-reading grayscale (1-5).
+    pub ereading_grayscale: u8,
+    /// E-reading temperature (-50 to +50).
+    pub ereading_temp: u8,
+}
+
+/// Everything a UI needs to render the current display settings.
+///
+/// Obtain with [`AsusController::full_status`]. All fields are resolved from the
+/// same [`ControllerState`] snapshot.
+#[derive(Debug)]
+pub struct FullStatus {
+    /// The active mode (an [`EReadingMode`](crate::EReadingMode) while e-reading is on).
+    pub mode: Box<dyn DisplayMode>,
+    /// Dimming as a percentage (0-100).
+    pub dimming_percent: i32,
+    /// Whether e-reading is active.
+    pub e_reading: bool,
+}
+
+/// A value together with when its parts were last updated.
+///
+/// Obtain with [`AsusController::get_state_timestamped`].
+#[derive(Debug, Clone)]
+pub struct Timestamped<T> {
+    /// The snapshot.
+    pub value: T,
+    /// When each group of fields in the snapshot was last updated.
+    pub updated: StateTimestamps,
+}
+
+/// When each group of [`ControllerState`] fields was last updated by a callback.
+///
+/// `None` means the field has not been reported since startup (or since
+/// [`AsusController::reset_cache`]) and still holds its default.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StateTimestamps {
+    /// `mode_id` and `is_monochrome`.
+    pub mode: Option<Instant>,
+    /// `dimming`.
+    pub dimming: Option<Instant>,
+    /// `manual_slider`.
+    pub manual_slider: Option<Instant>,
+    /// `eyecare_level`.
+    pub eyecare_level: Option<Instant>,
+    /// `ereading_grayscale` and `ereading_temp`.
+    pub ereading: Option<Instant>,
+}
+
+impl StateTimestamps {
+    /// Timestamps that are all unset.
+    pub const fn new() -> Self {
+        Self {
+            mode: None,
+            dimming: None,
+            manual_slider: None,
+            eyecare_level: None,
+            ereading: None,
+        }
+    }
+
+    /// Time since the least recently updated field, or `None` if any field was
+    /// never updated.
+    ///
+    /// Useful for deciding whether to re-sync with
+    /// [`sync_all_sliders`](crate::DisplayController::sync_all_sliders).
+    pub fn max_age(&self) -> Option<Duration> {
+        [
+            self.mode,
+            self.dimming,
+            self.manual_slider,
+            self.eyecare_level,
+            self.ereading,
+        ]
+        .into_iter()
+        .map(|updated| updated.map(|at| at.elapsed()))
+        .try_fold(Duration::ZERO, |oldest, age| Some(oldest.max(age?)))
+    }
+}
+
+/// A user-intended base dimming plus a stack of transient overrides, in splendid units.
+///
+/// Backs [`DisplayController::push_dimming_override`](crate::DisplayController::push_dimming_override)
+/// and related methods. Each method returns the level to apply, if any.
+#[derive(Debug, Default)]
+pub(crate) struct DimmingLayers {
+    base: Option<i32>,
+    overrides: Vec<i32>,
+}
+
+impl DimmingLayers {
+    /// Set the base level; it takes effect only when no override is active.
+    pub(crate) fn set_base(&mut self, level: i32) -> Option<i32> {
+        self.base = Some(level);
+        self.overrides.is_empty().then_some(level)
+    }
+
+    /// Push an override. Without a base yet, the `current` level becomes the base.
+    pub(crate) fn push(&mut self, level: i32, current: i32) -> i32 {
+        if self.base.is_none() && current >= 40 {
+            self.base = Some(current);
+        }
+        self.overrides.push(level);
+        level
+    }
+
+    /// Pop the newest override, returning the level that is effective again.
+    pub(crate) fn pop(&mut self) -> Option<i32> {
+        self.overrides.pop()?;
+        self.overrides.last().copied().or(self.base)
+    }
+
+    pub(crate) fn base(&self) -> Option<i32> {
+        self.base
+    }
 }
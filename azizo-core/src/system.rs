@@ -0,0 +1,299 @@
+//! Windows system queries used alongside the Splendid DLL.
+
+use log::debug;
+use std::sync::Mutex;
+use std::time::Duration;
+use windows_sys::Win32::Foundation::{CloseHandle, HANDLE, INVALID_HANDLE_VALUE, SYSTEMTIME};
+use windows_sys::Win32::Storage::FileSystem::{
+    CreateFileW, FILE_ATTRIBUTE_NORMAL, FILE_GENERIC_WRITE, FILE_SHARE_WRITE, OPEN_EXISTING,
+};
+use windows_sys::Win32::System::Console::{GetStdHandle, STD_OUTPUT_HANDLE, SetStdHandle};
+use windows_sys::Win32::System::Diagnostics::ToolHelp::{
+    CreateToolhelp32Snapshot, PROCESSENTRY32W, Process32FirstW, Process32NextW, TH32CS_SNAPPROCESS,
+};
+use windows_sys::Win32::System::Memory::{
+    CreateFileMappingW, FILE_MAP_READ, FILE_MAP_WRITE, MEMORY_MAPPED_VIEW_ADDRESS, MapViewOfFile,
+    OpenFileMappingW, PAGE_READWRITE, UnmapViewOfFile,
+};
+use windows_sys::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+use windows_sys::Win32::System::Registry::{HKEY_LOCAL_MACHINE, RRF_RT_REG_SZ, RegGetValueW};
+use windows_sys::Win32::System::SystemInformation::{GetLocalTime, GetTickCount};
+use windows_sys::Win32::System::Threading::GetCurrentProcessId;
+use windows_sys::Win32::UI::Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO};
+
+/// Registry copy of the SMBIOS system information, the same source WMI's
+/// `Win32_ComputerSystem.Model` reads from.
+const BIOS_KEY: &str = "HARDWARE\\DESCRIPTION\\System\\BIOS";
+
+/// Shared memory block holding the PID of the process that owns the controller.
+///
+/// Session-local, so each signed-in user can run their own instance.
+const OWNER_MAPPING_NAME: &str = "Local\\azizo-controller-owner";
+
+/// ASUS utilities known to send their own Splendid commands, as
+/// `(executable name, display name)` pairs.
+pub(crate) const CONFLICTING_APPS: &[(&str, &str)] = &[
+    ("ArmouryCrate.exe", "Armoury Crate"),
+    ("MyASUS.exe", "MyASUS"),
+    ("GameVisual.exe", "GameVisual"),
+];
+
+/// Executable names of all running processes.
+pub(crate) fn running_process_names() -> Vec<String> {
+    let mut names = Vec::new();
+
+    unsafe {
+        let snapshot = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0);
+        if snapshot == INVALID_HANDLE_VALUE {
+            debug!("failed to snapshot running processes");
+            return names;
+        }
+
+        let mut entry = PROCESSENTRY32W {
+            dwSize: std::mem::size_of::<PROCESSENTRY32W>() as u32,
+            ..Default::default()
+        };
+        let mut found = Process32FirstW(snapshot, &mut entry);
+        while found != 0 {
+            let len = entry
+                .szExeFile
+                .iter()
+                .position(|&c| c == 0)
+                .unwrap_or(entry.szExeFile.len());
+            names.push(String::from_utf16_lossy(&entry.szExeFile[..len]));
+            found = Process32NextW(snapshot, &mut entry);
+        }
+
+        CloseHandle(snapshot);
+    }
+
+    names
+}
+
+/// SMBIOS product name of the machine, e.g. `"Zenbook UX3402ZA"`.
+pub(crate) fn system_product_name() -> Option<String> {
+    let subkey: Vec<u16> = format!("{}\0", BIOS_KEY).encode_utf16().collect();
+    let value: Vec<u16> = "SystemProductName\0".encode_utf16().collect();
+
+    let mut buffer = [0u16; 256];
+    let mut size = std::mem::size_of_val(&buffer) as u32;
+    let result = unsafe {
+        RegGetValueW(
+            HKEY_LOCAL_MACHINE,
+            subkey.as_ptr(),
+            value.as_ptr(),
+            RRF_RT_REG_SZ,
+            std::ptr::null_mut(),
+            buffer.as_mut_ptr() as *mut std::ffi::c_void,
+            &mut size,
+        )
+    };
+
+    if result != 0 {
+        debug!("failed to read SystemProductName (error code: {})", result);
+        return None;
+    }
+    let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+    let name = String::from_utf16_lossy(&buffer[..len]).trim().to_string();
+    (!name.is_empty()).then_some(name)
+}
+
+/// Current local time as time since midnight.
+pub(crate) fn local_time_of_day() -> Duration {
+    let mut time = SYSTEMTIME::default();
+    unsafe { GetLocalTime(&mut time) };
+    Duration::from_secs(time.wHour as u64 * 3600 + time.wMinute as u64 * 60 + time.wSecond as u64)
+        + Duration::from_millis(time.wMilliseconds as u64)
+}
+
+/// Time since the last keyboard or mouse input, or `None` if unknown.
+pub(crate) fn idle_time() -> Option<Duration> {
+    let mut info = LASTINPUTINFO {
+        cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32,
+        dwTime: 0,
+    };
+    if unsafe { GetLastInputInfo(&mut info) } == 0 {
+        debug!("failed to read last input time");
+        return None;
+    }
+    // Both are tick counts that wrap after ~49 days
+    let now = unsafe { GetTickCount() };
+    Some(Duration::from_millis(now.wrapping_sub(info.dwTime) as u64))
+}
+
+/// Whether the machine runs on AC power, or `None` if unknown.
+pub(crate) fn on_ac_power() -> Option<bool> {
+    let mut status = SYSTEM_POWER_STATUS::default();
+    if unsafe { GetSystemPowerStatus(&mut status) } == 0 {
+        debug!("failed to read system power status");
+        return None;
+    }
+    match status.ACLineStatus {
+        0 => Some(false),
+        1 => Some(true),
+        _ => None,
+    }
+}
+
+fn owner_mapping_name() -> Vec<u16> {
+    format!("{}\0", OWNER_MAPPING_NAME).encode_utf16().collect()
+}
+
+/// Shared memory recording this process as the controller owner.
+///
+/// Windows deletes the block once no process has it open, so a crashed owner
+/// never leaves a stale PID behind. Dropping this clears the PID.
+pub(crate) struct OwnerRecord {
+    mapping: HANDLE,
+    view: MEMORY_MAPPED_VIEW_ADDRESS,
+}
+
+// Safety: The view is only written on creation and drop, which take ownership
+unsafe impl Send for OwnerRecord {}
+unsafe impl Sync for OwnerRecord {}
+
+impl OwnerRecord {
+    /// Record the current process as the owner, or `None` if the shared memory
+    /// could not be created.
+    pub(crate) fn claim() -> Option<Self> {
+        let name = owner_mapping_name();
+        unsafe {
+            let size = std::mem::size_of::<u32>() as u32;
+            let mapping = CreateFileMappingW(
+                INVALID_HANDLE_VALUE,
+                std::ptr::null(),
+                PAGE_READWRITE,
+                0,
+                size,
+                name.as_ptr(),
+            );
+            if mapping.is_null() {
+                debug!("failed to create the owner mapping");
+                return None;
+            }
+
+            let view = MapViewOfFile(mapping, FILE_MAP_WRITE, 0, 0, size as usize);
+            if view.Value.is_null() {
+                debug!("failed to map the owner mapping");
+                CloseHandle(mapping);
+                return None;
+            }
+
+            std::ptr::write_volatile(view.Value as *mut u32, GetCurrentProcessId());
+            Some(Self { mapping, view })
+        }
+    }
+}
+
+impl Drop for OwnerRecord {
+    fn drop(&mut self) {
+        unsafe {
+            std::ptr::write_volatile(self.view.Value as *mut u32, 0);
+            UnmapViewOfFile(self.view);
+            CloseHandle(self.mapping);
+        }
+    }
+}
+
+/// PID of the process recorded as the controller owner, if any.
+pub(crate) fn owner_pid() -> Option<u32> {
+    let name = owner_mapping_name();
+    unsafe {
+        let mapping = OpenFileMappingW(FILE_MAP_READ, 0, name.as_ptr());
+        if mapping.is_null() {
+            return None;
+        }
+
+        let view = MapViewOfFile(mapping, FILE_MAP_READ, 0, 0, std::mem::size_of::<u32>());
+        let pid = if view.Value.is_null() {
+            0
+        } else {
+            let pid = std::ptr::read_volatile(view.Value as *const u32);
+            UnmapViewOfFile(view);
+            pid
+        };
+        CloseHandle(mapping);
+
+        (pid != 0).then_some(pid)
+    }
+}
+
+/// Original stdout handle and the `NUL` handle replacing it, while any
+/// [`StdoutSuppression`] is alive, with the number of live guards.
+struct Redirect {
+    original: HANDLE,
+    nul: HANDLE,
+    count: usize,
+}
+
+// Safety: The handles are plain kernel handles, only used under the mutex
+unsafe impl Send for Redirect {}
+
+static STDOUT_REDIRECT: Mutex<Option<Redirect>> = Mutex::new(None);
+
+/// Points the process's stdout handle at `NUL` until dropped.
+///
+/// Guards may overlap, across threads too; the original handle comes back
+/// once the last one is dropped. Only output written through the handle
+/// looked up at write time is hidden, so a C runtime that cached it at
+/// startup still prints.
+pub(crate) struct StdoutSuppression {
+    // Whether this guard is counted in `STDOUT_REDIRECT`
+    active: bool,
+}
+
+impl StdoutSuppression {
+    pub(crate) fn start() -> Self {
+        let mut redirect = STDOUT_REDIRECT.lock().unwrap();
+        match redirect.as_mut() {
+            Some(redirect) => {
+                redirect.count += 1;
+                Self { active: true }
+            }
+            None => unsafe {
+                let nul: Vec<u16> = "NUL\0".encode_utf16().collect();
+                let handle = CreateFileW(
+                    nul.as_ptr(),
+                    FILE_GENERIC_WRITE,
+                    FILE_SHARE_WRITE,
+                    std::ptr::null(),
+                    OPEN_EXISTING,
+                    FILE_ATTRIBUTE_NORMAL,
+                    std::ptr::null_mut(),
+                );
+                if handle == INVALID_HANDLE_VALUE {
+                    debug!("failed to open NUL, leaving stdout as is");
+                    Self { active: false }
+                } else {
+                    let original = GetStdHandle(STD_OUTPUT_HANDLE);
+                    SetStdHandle(STD_OUTPUT_HANDLE, handle);
+                    *redirect = Some(Redirect {
+                        original,
+                        nul: handle,
+                        count: 1,
+                    });
+                    Self { active: true }
+                }
+            },
+        }
+    }
+}
+
+impl Drop for StdoutSuppression {
+    fn drop(&mut self) {
+        if !self.active {
+            return;
+        }
+        let mut redirect = STDOUT_REDIRECT.lock().unwrap();
+        if let Some(active) = redirect.as_mut() {
+            active.count -= 1;
+            if active.count == 0 {
+                unsafe {
+                    SetStdHandle(STD_OUTPUT_HANDLE, active.original);
+                    CloseHandle(active.nul);
+                }
+                *redirect = None;
+            }
+        }
+    }
+}
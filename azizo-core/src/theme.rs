@@ -0,0 +1,182 @@
+//! Follow the Windows light/dark theme.
+
+use crate::automation::{TaskKind, WatcherOptions};
+use crate::controller::DisplayController;
+use crate::modes::DisplayMode;
+
+use log::{debug, info};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::JoinHandle;
+use std::time::Duration;
+use windows_sys::Win32::System::Registry::{HKEY_CURRENT_USER, RRF_RT_REG_DWORD, RegGetValueW};
+
+const PERSONALIZE_KEY: &str = "Software\\Microsoft\\Windows\\CurrentVersion\\Themes\\Personalize";
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Number of consecutive polls a new theme must be observed before it is applied.
+const DEBOUNCE_POLLS: u32 = 2;
+
+/// Read the Windows `AppsUseLightTheme` preference.
+///
+/// Returns `Some(true)` for light mode, `Some(false)` for dark mode, and `None`
+/// if the registry value is missing or unreadable.
+pub fn apps_use_light_theme() -> Option<bool> {
+    let subkey: Vec<u16> = format!("{}\0", PERSONALIZE_KEY).encode_utf16().collect();
+    let value: Vec<u16> = "AppsUseLightTheme\0".encode_utf16().collect();
+
+    let mut data = 0u32;
+    let mut size = std::mem::size_of::<u32>() as u32;
+    let result = unsafe {
+        RegGetValueW(
+            HKEY_CURRENT_USER,
+            subkey.as_ptr(),
+            value.as_ptr(),
+            RRF_RT_REG_DWORD,
+            std::ptr::null_mut(),
+            &mut data as *mut u32 as *mut std::ffi::c_void,
+            &mut size,
+        )
+    };
+
+    if result != 0 {
+        debug!("failed to read AppsUseLightTheme (error code: {})", result);
+        return None;
+    }
+    Some(data != 0)
+}
+
+/// Switches the display mode to follow the Windows light/dark theme.
+///
+/// A background thread polls the `AppsUseLightTheme` registry value (every 2s by
+/// default, see [`WatcherOptions::poll_interval`]) and applies the light or dark
+/// mode when the theme changes. A change must be observed on consecutive polls
+/// before it is applied, so rapid toggling is debounced. The mode matching the
+/// theme at start is applied immediately.
+///
+/// Changes go through the [`AutomationGuard`](crate::AutomationGuard) given in the
+/// [`WatcherOptions`], so they are held back while automation is paused.
+///
+/// The thread stops when the watcher is stopped or dropped.
+///
+/// # Example
+///
+/// ```no_run
+/// use azizo_core::{AsusController, EyeCareMode, NormalMode, SystemThemeWatcher};
+/// use std::sync::Arc;
+///
+/// let controller = Arc::new(AsusController::new()?);
+/// let watcher = SystemThemeWatcher::spawn(
+///     controller,
+///     Box::new(NormalMode::new()),
+///     Box::new(EyeCareMode::new(3)?),
+/// );
+/// // ...
+/// watcher.stop();
+/// # Ok::<(), azizo_core::ControllerError>(())
+/// ```
+pub struct SystemThemeWatcher {
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl SystemThemeWatcher {
+    /// Start watching the system theme.
+    ///
+    /// `light` is applied while Windows uses the light theme, `dark` otherwise.
+    pub fn spawn(
+        controller: Arc<dyn DisplayController>,
+        light: Box<dyn DisplayMode>,
+        dark: Box<dyn DisplayMode>,
+    ) -> Self {
+        Self::spawn_with(controller, light, dark, WatcherOptions::default())
+    }
+
+    /// Start watching the system theme with custom options.
+    pub fn spawn_with(
+        controller: Arc<dyn DisplayController>,
+        light: Box<dyn DisplayMode>,
+        dark: Box<dyn DisplayMode>,
+        options: WatcherOptions,
+    ) -> Self {
+        let light: Arc<dyn DisplayMode> = Arc::from(light);
+        let dark: Arc<dyn DisplayMode> = Arc::from(dark);
+        let poll_interval = options.poll_interval_or(DEFAULT_POLL_INTERVAL);
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = Arc::clone(&stop);
+        let task = options.tasks.as_ref().map(|tasks| {
+            (
+                tasks.clone(),
+                tasks.register(TaskKind::ThemeWatcher, Arc::clone(&stop)),
+            )
+        });
+
+        let handle = std::thread::spawn(move || {
+            let mut applied: Option<bool> = None;
+            let mut pending: Option<(bool, u32)> = None;
+
+            while !thread_stop.load(Ordering::SeqCst) {
+                if let Some(light_theme) = apps_use_light_theme() {
+                    if applied.is_none() {
+                        pending = Some((light_theme, DEBOUNCE_POLLS));
+                    } else if applied != Some(light_theme) {
+                        let seen = match pending {
+                            Some((theme, count)) if theme == light_theme => count + 1,
+                            _ => 1,
+                        };
+                        pending = Some((light_theme, seen));
+                    } else {
+                        pending = None;
+                    }
+
+                    if let Some((theme, count)) = pending
+                        && count >= DEBOUNCE_POLLS
+                    {
+                        let mode = Arc::clone(if theme { &light } else { &dark });
+                        let controller = Arc::clone(&controller);
+                        info!(
+                            "system theme is {}, applying {}",
+                            if theme { "light" } else { "dark" },
+                            mode.describe()
+                        );
+                        options.guard.run("system theme", move || {
+                            if let Err(e) = controller.set_mode(mode.as_ref()) {
+                                controller.report_error(&e);
+                            }
+                        });
+                        applied = Some(theme);
+                        pending = None;
+                    }
+                }
+                std::thread::sleep(poll_interval);
+            }
+
+            if let Some((tasks, id)) = task {
+                tasks.unregister(id);
+            }
+        });
+
+        Self {
+            stop,
+            handle: Some(handle),
+        }
+    }
+
+    /// Stop watching and wait for the background thread to exit.
+    pub fn stop(mut self) {
+        self.shutdown();
+    }
+
+    fn shutdown(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for SystemThemeWatcher {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
@@ -0,0 +1,121 @@
+//! Keep the controller on a dedicated thread and send it commands.
+
+use crate::controller::DisplayController;
+use crate::error::ControllerError;
+use crate::modes::ModeSnapshot;
+use crate::state::ControllerState;
+
+use log::debug;
+use std::sync::mpsc::{self, Receiver, Sender};
+
+/// Reply channel for a command's result.
+pub type Reply<T> = Receiver<Result<T, ControllerError>>;
+
+enum Command {
+    GetState(Sender<Result<ControllerState, ControllerError>>),
+    SetMode(ModeSnapshot, Sender<Result<(), ControllerError>>),
+    SetDimming(i32, Sender<Result<(), ControllerError>>),
+    SetDimmingPercent(i32, Sender<Result<(), ControllerError>>),
+    ToggleEReading(Sender<Result<ModeSnapshot, ControllerError>>),
+}
+
+/// Cheap, cloneable handle for controlling a controller that lives on its own thread.
+///
+/// Each method enqueues a command and returns a [`Reply`] receiving its result
+/// once the worker thread has run it. Commands run one at a time in the order
+/// they were sent, so all DLL access stays on the worker thread. Callers that
+/// don't need the result can drop the reply.
+///
+/// The worker thread exits, dropping the controller, once every sink is dropped.
+/// Create one with [`AsusController::spawn_worker`](crate::AsusController::spawn_worker)
+/// or [`CommandSink::spawn`].
+///
+/// # Example
+///
+/// ```no_run
+/// use azizo_core::{AsusController, ModeSnapshot};
+///
+/// let sink = AsusController::new()?.spawn_worker();
+/// let hotkeys = sink.clone();
+/// std::thread::spawn(move || {
+///     let _ = hotkeys.toggle_e_reading();
+/// });
+/// sink.set_mode(ModeSnapshot::EyeCare(2)).recv().unwrap()?;
+/// # Ok::<(), azizo_core::ControllerError>(())
+/// ```
+#[derive(Clone)]
+pub struct CommandSink {
+    commands: Sender<Command>,
+}
+
+impl CommandSink {
+    /// Move a controller to a new worker thread and return a sink for it.
+    pub fn spawn(controller: impl DisplayController + 'static) -> Self {
+        let (commands, received) = mpsc::channel();
+        std::thread::spawn(move || {
+            for command in received {
+                run(&controller, command);
+            }
+            debug!("all command sinks dropped, stopping worker");
+        });
+        Self { commands }
+    }
+
+    /// Get the cached state.
+    pub fn get_state(&self) -> Reply<ControllerState> {
+        self.send(Command::GetState)
+    }
+
+    /// Apply a mode.
+    pub fn set_mode(&self, mode: ModeSnapshot) -> Reply<()> {
+        self.send(|reply| Command::SetMode(mode, reply))
+    }
+
+    /// Set dimming in splendid units (40-100).
+    pub fn set_dimming(&self, level: i32) -> Reply<()> {
+        self.send(|reply| Command::SetDimming(level, reply))
+    }
+
+    /// Set dimming as a percentage (0-100).
+    pub fn set_dimming_percent(&self, percent: i32) -> Reply<()> {
+        self.send(|reply| Command::SetDimmingPercent(percent, reply))
+    }
+
+    /// Toggle e-reading mode, replying with the mode that was applied.
+    pub fn toggle_e_reading(&self) -> Reply<ModeSnapshot> {
+        self.send(Command::ToggleEReading)
+    }
+
+    fn send<T>(
+        &self,
+        command: impl FnOnce(Sender<Result<T, ControllerError>>) -> Command,
+    ) -> Reply<T> {
+        let (reply, result) = mpsc::channel();
+        // The worker only exits once every sink is gone, so this can't fail
+        let _ = self.commands.send(command(reply));
+        result
+    }
+}
+
+fn run(controller: &dyn DisplayController, command: Command) {
+    // A dropped reply just means the caller didn't want the result
+    match command {
+        Command::GetState(reply) => {
+            let _ = reply.send(Ok(controller.get_state()));
+        }
+        Command::SetMode(mode, reply) => {
+            let result = mode.to_mode().and_then(|mode| controller.set_mode(&*mode));
+            let _ = reply.send(result);
+        }
+        Command::SetDimming(level, reply) => {
+            let _ = reply.send(controller.set_dimming(level));
+        }
+        Command::SetDimmingPercent(percent, reply) => {
+            let _ = reply.send(controller.set_dimming_percent(percent));
+        }
+        Command::ToggleEReading(reply) => {
+            let result = controller.toggle_e_reading().map(|mode| mode.snapshot());
+            let _ = reply.send(result);
+        }
+    }
+}
@@ -1,8 +1,8 @@
 use std::sync::Arc;
 
 use azizo_core::{
-    AsusController, ControllerError, DisplayController, EReadingMode, EyeCareMode, ManualMode,
-    NormalMode, VividMode,
+    AsusController, DisplayController, DisplayMode, EReadingMode, EyeCareMode, ManualMode,
+    ModeKind, NormalMode, VividMode,
 };
 use iced::keyboard::{self, Event as KeyboardEvent, Key};
 use iced::widget::{button, column, container, row, slider, text, toggler};
@@ -19,12 +19,23 @@ pub fn main() -> iced::Result {
         .run()
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum ModeType {
-    Normal,
-    Vivid,
-    Manual,
-    EyeCare,
+/// The mode selected by the mode buttons, from the mode ID the controller reports.
+fn selected_mode(mode_id: i32) -> ModeKind {
+    match ModeKind::from_mode_id(mode_id) {
+        Some(ModeKind::EReading) | None => ModeKind::Normal,
+        Some(kind) => kind,
+    }
+}
+
+fn build_mode(kind: ModeKind, manual_value: i32, eyecare_level: i32) -> Box<dyn DisplayMode> {
+    match kind {
+        ModeKind::Vivid => Box::new(VividMode::new()),
+        // Convert UI -50 to +50 to hardware 0-100
+        ModeKind::Manual => Box::new(ManualMode::clamped(manual_value + 50)),
+        ModeKind::EyeCare => Box::new(EyeCareMode::clamped(eyecare_level)),
+        // E-Reading has its own toggle, not a mode button
+        ModeKind::Normal | ModeKind::EReading => Box::new(NormalMode::new()),
+    }
 }
 
 struct AzizoApp {
@@ -32,8 +43,8 @@ struct AzizoApp {
     error_message: Option<String>,
 
     // Display state
-    dimming_percent: i32,
-    current_mode: ModeType,
+    dimming: i32, // Splendid units (40-100), the source of truth for the slider
+    current_mode: ModeKind,
     is_ereading: bool,
 
     // Mode sliders
@@ -54,7 +65,7 @@ enum Message {
     DecreaseDimming,
 
     // Mode selection
-    SetMode(ModeType),
+    SetMode(ModeKind),
     ToggleEReading(bool),
 
     // Mode-specific sliders
@@ -78,8 +89,8 @@ impl Default for AzizoApp {
         let mut app = Self {
             controller: None,
             error_message: None,
-            dimming_percent: 100,
-            current_mode: ModeType::Normal,
+            dimming: 100,
+            current_mode: ModeKind::Normal,
             is_ereading: false,
             manual_value: 0, // UI uses -50 to +50, hardware uses 0-100
             eyecare_level: 2,
@@ -98,21 +109,30 @@ impl Default for AzizoApp {
                     app.error_message = Some(format!("Sync error: {}", e));
                 } else {
                     let state = controller.get_state();
-                    app.dimming_percent = AsusController::dimming_to_percent(state.dimming);
+                    app.dimming = state.dimming;
                     app.manual_value = state.manual_slider as i32 - 50; // Convert hardware 0-100 to UI -50 to +50
                     app.eyecare_level = state.eyecare_level as i32;
                     app.ereading_grayscale = state.ereading_grayscale as i32;
                     app.ereading_temp = state.ereading_temp as i32;
                     app.is_ereading = state.is_monochrome;
 
-                    // Determine current mode
-                    app.current_mode = match state.mode_id {
-                        1 => ModeType::Normal,
-                        2 => ModeType::Vivid,
-                        6 => ModeType::Manual,
-                        7 => ModeType::EyeCare,
-                        _ => ModeType::Normal,
-                    };
+                    app.current_mode = selected_mode(state.mode_id);
+                }
+
+                if let Ok(false) = controller.is_splendid_enabled() {
+                    app.add_toast(
+                        "Splendid not responding",
+                        "Splendid may be disabled in Armoury Crate",
+                        Status::Warning,
+                    );
+                }
+
+                for name in controller.detect_conflicting_apps() {
+                    app.add_toast(
+                        "Conflicting app",
+                        format!("{} is open and may override changes", name),
+                        Status::Warning,
+                    );
                 }
 
                 app.controller = Some(controller);
@@ -135,6 +155,15 @@ impl AzizoApp {
         });
     }
 
+    fn adjust_dimming(&mut self, delta: i32) {
+        if let Some(ref controller) = self.controller {
+            match controller.adjust_dimming_percent(delta) {
+                Ok(percent) => self.dimming = AsusController::percent_to_dimming(percent),
+                Err(e) => self.error_message = Some(format!("Dimming error: {}", e)),
+            }
+        }
+    }
+
     fn update(&mut self, message: Message) -> Task<Message> {
         // Clear previous errors on new actions
         if !matches!(
@@ -146,41 +175,24 @@ impl AzizoApp {
 
         match message {
             Message::DimmingChanged(value) => {
-                self.dimming_percent = value;
+                // Keep splendid units as the source of truth so repeated +/- don't drift
+                self.dimming = AsusController::percent_to_dimming(value.clamp(0, 100));
                 if let Some(ref controller) = self.controller {
-                    if let Err(e) = controller.set_dimming_percent(value) {
+                    if let Err(e) = controller.set_base_dimming_percent(value.clamp(0, 100)) {
                         self.error_message = Some(format!("Dimming error: {}", e));
                     }
                 }
             }
 
-            Message::IncreaseDimming => {
-                let new_value = (self.dimming_percent + 10).min(100);
-                return self.update(Message::DimmingChanged(new_value));
-            }
+            Message::IncreaseDimming => self.adjust_dimming(10),
 
-            Message::DecreaseDimming => {
-                let new_value = (self.dimming_percent - 10).max(0);
-                return self.update(Message::DimmingChanged(new_value));
-            }
+            Message::DecreaseDimming => self.adjust_dimming(-10),
 
             Message::SetMode(mode) => {
                 self.current_mode = mode;
                 if let Some(ref controller) = self.controller {
-                    let result: Result<(), ControllerError> = match mode {
-                        ModeType::Normal => controller.set_mode(&NormalMode::new()),
-                        ModeType::Vivid => controller.set_mode(&VividMode::new()),
-                        ModeType::Manual => match ManualMode::new((self.manual_value + 50) as u8) {
-                            // Convert UI -50 to +50 to hardware 0-100
-                            Ok(m) => controller.set_mode(&m),
-                            Err(e) => Err(e),
-                        },
-                        ModeType::EyeCare => match EyeCareMode::new(self.eyecare_level as u8) {
-                            Ok(m) => controller.set_mode(&m),
-                            Err(e) => Err(e),
-                        },
-                    };
-                    if let Err(e) = result {
+                    let target = build_mode(mode, self.manual_value, self.eyecare_level);
+                    if let Err(e) = controller.set_mode(&*target) {
                         self.error_message = Some(format!("Mode error: {}", e));
                     }
                 }
@@ -191,18 +203,11 @@ impl AzizoApp {
                 if let Some(ref controller) = self.controller {
                     if enabled {
                         // Enable e-reading
-                        match EReadingMode::new(
+                        if let Err(e) = controller.enable_e_reading(
                             self.ereading_grayscale as u8,
                             self.ereading_temp as i8,
                         ) {
-                            Ok(mode) => {
-                                if let Err(e) = controller.set_mode(&mode) {
-                                    self.error_message = Some(format!("E-Reading error: {}", e));
-                                }
-                            }
-                            Err(e) => {
-                                self.error_message = Some(format!("E-Reading error: {}", e));
-                            }
+                            self.error_message = Some(format!("E-Reading error: {}", e));
                         }
                     } else {
                         // Disable - restore previous mode
@@ -215,13 +220,12 @@ impl AzizoApp {
 
             Message::ManualSliderChanged(value) => {
                 self.manual_value = value;
-                if self.current_mode == ModeType::Manual {
+                if self.current_mode == ModeKind::Manual {
                     if let Some(ref controller) = self.controller {
                         // Convert UI -50 to +50 to hardware 0-100
-                        if let Ok(mode) = ManualMode::new((value + 50) as u8) {
-                            if let Err(e) = controller.set_mode(&mode) {
-                                self.error_message = Some(format!("Manual error: {}", e));
-                            }
+                        let mode = ManualMode::clamped(value + 50);
+                        if let Err(e) = controller.set_mode(&mode) {
+                            self.error_message = Some(format!("Manual error: {}", e));
                         }
                     }
                 }
@@ -229,12 +233,11 @@ impl AzizoApp {
 
             Message::EyeCareSliderChanged(value) => {
                 self.eyecare_level = value;
-                if self.current_mode == ModeType::EyeCare {
+                if self.current_mode == ModeKind::EyeCare {
                     if let Some(ref controller) = self.controller {
-                        if let Ok(mode) = EyeCareMode::new(value as u8) {
-                            if let Err(e) = controller.set_mode(&mode) {
-                                self.error_message = Some(format!("EyeCare error: {}", e));
-                            }
+                        let mode = EyeCareMode::clamped(value);
+                        if let Err(e) = controller.set_mode(&mode) {
+                            self.error_message = Some(format!("EyeCare error: {}", e));
                         }
                     }
                 }
@@ -273,21 +276,14 @@ impl AzizoApp {
                     match controller.sync_all_sliders() {
                         Ok(()) => {
                             let state = controller.get_state();
-                            self.dimming_percent =
-                                AsusController::dimming_to_percent(state.dimming);
+                            self.dimming = state.dimming;
                             self.manual_value = state.manual_slider as i32 - 50; // Convert hardware 0-100 to UI -50 to +50
                             self.eyecare_level = state.eyecare_level as i32;
                             self.ereading_grayscale = state.ereading_grayscale as i32;
                             self.ereading_temp = state.ereading_temp as i32;
                             self.is_ereading = state.is_monochrome;
 
-                            self.current_mode = match state.mode_id {
-                                1 => ModeType::Normal,
-                                2 => ModeType::Vivid,
-                                6 => ModeType::Manual,
-                                7 => ModeType::EyeCare,
-                                _ => ModeType::Normal,
-                            };
+                            self.current_mode = selected_mode(state.mode_id);
                             self.add_toast(
                                 "Synced!",
                                 "Hardware state synchronized",
@@ -345,23 +341,24 @@ impl AzizoApp {
         };
 
         // Dimming slider
+        let dimming_percent = AsusController::dimming_to_percent(self.dimming);
         let dimming_section = column![
-            text(format!("Dimming: {}%", self.dimming_percent)).size(16),
-            slider(0..=100, self.dimming_percent, Message::DimmingChanged).step(10),
+            text(format!("Dimming: {}%", dimming_percent)).size(16),
+            slider(0..=100, dimming_percent, Message::DimmingChanged).step(10),
         ]
         .spacing(5);
 
         // Mode buttons
         let mode_buttons = row![
-            mode_button("Normal", ModeType::Normal, self.current_mode),
-            mode_button("Vivid", ModeType::Vivid, self.current_mode),
-            mode_button("Manual", ModeType::Manual, self.current_mode),
-            mode_button("Eye Care", ModeType::EyeCare, self.current_mode),
+            mode_button(ModeKind::Normal, self),
+            mode_button(ModeKind::Vivid, self),
+            mode_button(ModeKind::Manual, self),
+            mode_button(ModeKind::EyeCare, self),
         ]
         .spacing(10);
 
         // Manual slider (only shown when Manual mode is selected)
-        let manual_section = if self.current_mode == ModeType::Manual {
+        let manual_section = if self.current_mode == ModeKind::Manual {
             column![
                 text(format!("Manual Temperature: {}", self.manual_value)).size(14),
                 slider(-50..=50, self.manual_value, Message::ManualSliderChanged).step(1),
@@ -372,7 +369,7 @@ impl AzizoApp {
         };
 
         // Eye Care slider (only shown when EyeCare mode is selected)
-        let eyecare_section = if self.current_mode == ModeType::EyeCare {
+        let eyecare_section = if self.current_mode == ModeKind::EyeCare {
             column![
                 text(format!("Eye Care Level: {}", self.eyecare_level)).size(14),
                 slider(0..=4, self.eyecare_level, Message::EyeCareSliderChanged).step(1),
@@ -446,9 +443,9 @@ impl AzizoApp {
     }
 }
 
-fn mode_button(label: &str, mode: ModeType, current: ModeType) -> Element<'_, Message> {
-    let btn = button(text(label));
-    if mode == current {
+fn mode_button(mode: ModeKind, app: &AzizoApp) -> Element<'static, Message> {
+    let btn = button(text(mode.name()));
+    if mode == app.current_mode {
         // Selected state - don't allow clicking
         btn.into()
     } else {